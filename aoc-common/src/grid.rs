@@ -0,0 +1,214 @@
+use std::fmt;
+use std::ops::{Index, IndexMut};
+use std::str::FromStr;
+
+use crate::{lines, ParseError};
+
+/// Position of a cell as `(row, column)`, the same order as `grid[i][j]`
+pub type Pos = (usize, usize);
+
+const OFFSETS4: [(isize, isize); 4] = [(-1, 0), (0, -1), (0, 1), (1, 0)];
+const OFFSETS8: [(isize, isize); 8] = [(-1, -1), (-1, 0), (-1, 1), (0, -1), (0, 1), (1, -1), (1, 0), (1, 1)];
+
+/// A rectangular 2D grid, stored row by row
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Grid<T> {
+    width: usize,
+    height: usize,
+    cells: Vec<T>,
+}
+
+impl<T> Grid<T> {
+    pub fn new(width: usize, height: usize, fill: T) -> Self
+    where
+        T: Clone,
+    {
+        Grid { width, height, cells: vec![fill; width * height] }
+    }
+
+    /// Build a grid by calling `f` on every position in row order
+    pub fn from_fn<F: FnMut(Pos) -> T>(width: usize, height: usize, mut f: F) -> Self {
+        let cells = (0..height).flat_map(|i| (0..width).map(move |j| (i, j))).map(&mut f).collect();
+        Grid { width, height, cells }
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    pub fn contains(&self, (i, j): Pos) -> bool {
+        i < self.height && j < self.width
+    }
+
+    pub fn get(&self, pos: Pos) -> Option<&T> {
+        match self.contains(pos) {
+            true => Some(&self.cells[pos.0 * self.width + pos.1]),
+            false => None,
+        }
+    }
+
+    pub fn get_mut(&mut self, pos: Pos) -> Option<&mut T> {
+        match self.contains(pos) {
+            true => Some(&mut self.cells[pos.0 * self.width + pos.1]),
+            false => None,
+        }
+    }
+
+    pub fn row(&self, i: usize) -> &[T] {
+        &self.cells[i * self.width..(i + 1) * self.width]
+    }
+
+    pub fn rows(&self) -> impl Iterator<Item = &[T]> {
+        self.cells.chunks(self.width.max(1)).take(self.height)
+    }
+
+    pub fn column(&self, j: usize) -> impl Iterator<Item = &T> {
+        assert!(j < self.width, "column {} out of range for width {}", j, self.width);
+        self.cells.iter().skip(j).step_by(self.width)
+    }
+
+    /// Every position in row order
+    pub fn positions(&self) -> impl Iterator<Item = Pos> {
+        let width = self.width;
+        (0..self.height).flat_map(move |i| (0..width).map(move |j| (i, j)))
+    }
+
+    /// Every cell in row order
+    pub fn iter(&self) -> impl Iterator<Item = &T> {
+        self.cells.iter()
+    }
+
+    pub fn iter_mut(&mut self) -> impl Iterator<Item = &mut T> {
+        self.cells.iter_mut()
+    }
+
+    /// Up, left, right and down neighbours of `pos` that lie inside the grid
+    pub fn neighbors4(&self, pos: Pos) -> impl Iterator<Item = Pos> {
+        self.offset_positions(pos, &OFFSETS4)
+    }
+
+    /// Neighbours of `pos` including diagonals, clipped to the grid
+    pub fn neighbors8(&self, pos: Pos) -> impl Iterator<Item = Pos> {
+        self.offset_positions(pos, &OFFSETS8)
+    }
+
+    fn offset_positions(&self, (i, j): Pos, offsets: &'static [(isize, isize)]) -> impl Iterator<Item = Pos> {
+        let (width, height) = (self.width, self.height);
+        offsets.iter().filter_map(move |&(di, dj)| {
+            let x = i.checked_add_signed(di)?;
+            let y = j.checked_add_signed(dj)?;
+            match x < height && y < width {
+                true => Some((x, y)),
+                false => None,
+            }
+        })
+    }
+
+    pub fn map<U, F: FnMut(&T) -> U>(&self, f: F) -> Grid<U> {
+        Grid { width: self.width, height: self.height, cells: self.cells.iter().map(f).collect() }
+    }
+}
+
+impl<T> Index<Pos> for Grid<T> {
+    type Output = T;
+
+    fn index(&self, (i, j): Pos) -> &T {
+        assert!(j < self.width, "column {} out of range for width {}", j, self.width);
+        &self.cells[i * self.width + j]
+    }
+}
+
+impl<T> IndexMut<Pos> for Grid<T> {
+    fn index_mut(&mut self, (i, j): Pos) -> &mut T {
+        assert!(j < self.width, "column {} out of range for width {}", j, self.width);
+        &mut self.cells[i * self.width + j]
+    }
+}
+
+/// A cell that is written as a single character, like a digit or `#`
+pub trait Cell: Sized {
+    /// Description used in parse errors
+    const EXPECTED: &'static str;
+
+    fn from_char(c: char) -> Option<Self>;
+
+    fn to_char(&self) -> char;
+}
+
+impl Cell for u8 {
+    const EXPECTED: &'static str = "a digit";
+
+    fn from_char(c: char) -> Option<Self> {
+        c.to_digit(10).map(|d| d as u8)
+    }
+
+    fn to_char(&self) -> char {
+        std::char::from_digit(*self as u32, 10).unwrap_or('?')
+    }
+}
+
+/// `#` is set, `.` is clear
+impl Cell for bool {
+    const EXPECTED: &'static str = "'#' or '.'";
+
+    fn from_char(c: char) -> Option<Self> {
+        match c {
+            '#' => Some(true),
+            '.' => Some(false),
+            _ => None,
+        }
+    }
+
+    fn to_char(&self) -> char {
+        match self {
+            true => '#',
+            false => '.',
+        }
+    }
+}
+
+impl<T: Cell> Grid<T> {
+    /// Parse one row per line. Every row must be as long as the first.
+    pub fn from_lines<S: AsRef<str>>(input: &[S]) -> Result<Self, ParseError> {
+        let first = input.first().map(|l| l.as_ref()).unwrap_or("");
+        if first.is_empty() {
+            return Err(ParseError::new(1, 1, "", "a row of cells"));
+        }
+        let width = first.chars().count();
+        let mut cells = Vec::with_capacity(width * input.len());
+        for (i, line) in input.iter().enumerate() {
+            let line = line.as_ref();
+            for (j, c) in line.char_indices() {
+                let cell = T::from_char(c).ok_or_else(|| ParseError::new(i + 1, j + 1, &line[j..j + c.len_utf8()], T::EXPECTED))?;
+                cells.push(cell);
+            }
+            if cells.len() != (i + 1) * width {
+                return Err(ParseError::new(i + 1, 1, line, &format!("{} cells", width)));
+            }
+        }
+        Ok(Grid { width, height: input.len(), cells })
+    }
+}
+
+impl<T: Cell> FromStr for Grid<T> {
+    type Err = ParseError;
+
+    fn from_str(s: &str) -> Result<Self, ParseError> {
+        Grid::from_lines(&lines(s))
+    }
+}
+
+/// One line per row, ending with a newline
+impl<T: Cell> fmt::Display for Grid<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for row in self.rows() {
+            let line: String = row.iter().map(Cell::to_char).collect();
+            writeln!(f, "{}", line)?;
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,48 @@
+use std::fs;
+use std::io::Read;
+use std::path::Path;
+use std::str::FromStr;
+
+use crate::{Error, ParseError};
+
+/// Read a whole input file into memory
+pub fn read_input<P: AsRef<Path>>(filename: P) -> Result<String, Error> {
+    Ok(fs::read_to_string(filename)?)
+}
+
+/// Read everything from `reader` into memory, e.g. `io::stdin()` or a pipe
+pub fn read_from<R: Read>(mut reader: R) -> Result<String, Error> {
+    let mut input = String::new();
+    reader.read_to_string(&mut input)?;
+    Ok(input)
+}
+
+/// Split input into owned lines. Handles both `\n` and `\r\n` endings,
+/// and a missing newline at the end of the last line.
+pub fn lines(input: &str) -> Vec<String> {
+    input.lines().map(|l| l.to_owned()).collect()
+}
+
+/// Read an input file as a list of lines
+pub fn read_lines<P: AsRef<Path>>(filename: P) -> Result<Vec<String>, Error> {
+    read_input(filename).map(|s| lines(&s))
+}
+
+/// Parse every line of `input` as a `T`
+pub fn parse_lines<T: FromStr>(input: &str, expected: &str) -> Result<Vec<T>, ParseError> {
+    input
+        .lines()
+        .enumerate()
+        .map(|(i, l)| l.parse().map_err(|_| ParseError::new(i + 1, 1, l, expected)))
+        .collect()
+}
+
+/// Read an input file and parse every line as a `T`
+pub fn read_parsed<T, P>(filename: P) -> Result<Vec<T>, Error>
+where
+    T: FromStr,
+    P: AsRef<Path>,
+{
+    let expected = std::any::type_name::<T>();
+    Ok(parse_lines(&read_input(filename)?, expected)?)
+}
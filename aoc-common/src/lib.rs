@@ -0,0 +1,130 @@
+//! Helpers shared by every day of Advent of Code 2021
+mod error;
+mod grid;
+mod input;
+mod parse;
+mod rng;
+mod solution;
+
+pub use error::{Error, ParseError};
+pub use grid::{Cell, Grid, Pos};
+pub use input::{lines, parse_lines, read_from, read_input, read_lines, read_parsed};
+pub use parse::{column, parse_digits, parse_field, split_field};
+pub use rng::Rng;
+pub use solution::{Answer, Solution};
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    #[test]
+    fn split_lines() {
+        assert_eq!(lines("a\nb\r\nc"), vec!["a", "b", "c"]);
+        assert_eq!(lines("a\nb\n"), vec!["a", "b"]);
+        assert!(lines("").is_empty());
+    }
+
+    #[test]
+    fn missing_file() {
+        match read_lines("does-not-exist") {
+            Err(Error::Io(_)) => (),
+            other => panic!("expected Io error, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn parsed_reports_line() {
+        let path = std::env::temp_dir().join("aoc-common-parsed-test");
+        std::fs::write(&path, "1\n2\nthree\n").unwrap();
+        match read_parsed::<u64, _>(&path) {
+            Err(Error::Parse(e)) => {
+                assert_eq!(e, ParseError::new(3, 1, "three", "u64"));
+                assert_eq!(e.to_string(), "line 3, column 1: expected u64, found \"three\"");
+            }
+            other => panic!("expected Parse error, got {:?}", other),
+        }
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn fields() {
+        let line = "12,x3 -> 7";
+        let (left, right) = split_field(1, line, line, " -> ", "two points").unwrap();
+        let (x, y) = split_field(1, line, left, ",", "x,y").unwrap();
+        assert_eq!(parse_field::<u32>(1, line, x, "a number"), Ok(12));
+        assert_eq!(parse_field::<u32>(1, line, y, "a number"), Err(ParseError::new(1, 4, "x3", "a number")));
+        assert_eq!(column(line, right), 10);
+        assert_eq!(column(line, "elsewhere"), 1);
+        assert_eq!(
+            ParseError::new(5, 1, "", "a board row").to_string(),
+            "line 5, column 1: expected a board row, found nothing"
+        );
+    }
+
+    #[test]
+    fn grid() {
+        let grid: Grid<u8> = "123\n456\n".parse().unwrap();
+        assert_eq!((grid.width(), grid.height()), (3, 2));
+        assert_eq!(grid[(1, 2)], 6);
+        assert_eq!(grid.get((2, 0)), None);
+        assert_eq!(grid.row(1), &[4, 5, 6]);
+        assert_eq!(grid.column(1).copied().collect::<Vec<u8>>(), vec![2, 5]);
+        assert_eq!(grid.neighbors4((0, 0)).collect::<Vec<Pos>>(), vec![(0, 1), (1, 0)]);
+        assert_eq!(grid.neighbors8((1, 1)).count(), 5);
+        assert_eq!(grid.positions().last(), Some((1, 2)));
+        assert_eq!(grid.to_string(), "123\n456\n");
+
+        let dots: Grid<bool> = ".#\n#.".parse().unwrap();
+        assert_eq!(dots.iter().filter(|&&v| v).count(), 2);
+        assert_eq!(dots.map(|&v| !v).to_string(), "#.\n.#\n");
+        assert_eq!("12\n3".parse::<Grid<u8>>(), Err(ParseError::new(2, 1, "3", "2 cells")));
+        assert_eq!("#.\n#x".parse::<Grid<bool>>(), Err(ParseError::new(2, 2, "x", "'#' or '.'")));
+        assert!("".parse::<Grid<bool>>().is_err());
+    }
+
+    #[test]
+    fn rng() {
+        let mut a = Rng::new(7);
+        let mut b = Rng::new(7);
+        let draws: Vec<i64> = (0..1000).map(|_| a.range(-3..=3)).collect();
+        assert!(draws.iter().all(|v| (-3..=3).contains(v)));
+        assert!((-3..=3).all(|v| draws.contains(&v)));
+        assert_eq!(draws, (0..1000).map(|_| b.range(-3..=3)).collect::<Vec<i64>>());
+        assert!((0..100).all(|_| a.below(5) < 5));
+        assert_ne!(Rng::new(1).next_u64(), Rng::new(2).next_u64());
+    }
+
+    /// Sum of one number per line
+    struct Sum(u64);
+
+    impl Solution for Sum {
+        const DAY: u32 = 0;
+
+        fn parse(input: &str) -> Result<Self, Error> {
+            Ok(Sum(parse_lines::<u64>(input, "a number")?.iter().sum()))
+        }
+
+        fn part1(&self) -> Answer {
+            self.0.into()
+        }
+    }
+
+    #[test]
+    fn input_sources() {
+        assert_eq!(Sum::parse("1\n2\n3\n").unwrap().part1(), Answer::Number(6));
+        assert_eq!(Sum::from_reader("4\r\n5".as_bytes()).unwrap().part1(), Answer::Number(9));
+        assert_eq!(Sum::from_reader("4\n".as_bytes()).unwrap().part2(), Answer::Unsolved);
+        assert!(matches!(Sum::from_reader("4\nx".as_bytes()), Err(Error::Parse(_))));
+        assert!(matches!(Sum::from_file("does-not-exist"), Err(Error::Io(_))));
+    }
+
+    #[test]
+    fn answers() {
+        assert_eq!(Answer::from(42u64), Answer::Number(42));
+        assert_eq!(Answer::from(-3i32).to_string(), "-3");
+        assert_eq!(Answer::from(usize::MAX).to_string(), usize::MAX.to_string());
+        assert_eq!(Answer::from(i128::MAX as u128), Answer::Number(i128::MAX));
+        assert_eq!(Answer::from(i128::MAX as u128 + 1).to_string(), "170141183460469231731687303715884105728");
+        assert_eq!(Answer::from(u128::MAX).to_string(), u128::MAX.to_string());
+        assert_eq!(Answer::from("#.\n.#\n").to_string(), "#.\n.#\n");
+    }
+}
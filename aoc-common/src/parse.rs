@@ -0,0 +1,37 @@
+use std::str::FromStr;
+
+use crate::ParseError;
+
+/// 1-based column at which `part` starts, where `part` is a slice of `line`
+/// as returned by `split`, `trim` and friends. Falls back to column 1 for
+/// text that does not come from `line`.
+pub fn column(line: &str, part: &str) -> usize {
+    let offset = (part.as_ptr() as usize).wrapping_sub(line.as_ptr() as usize);
+    if offset <= line.len() {
+        offset + 1
+    } else {
+        1
+    }
+}
+
+/// Parse `part`, a slice of the 1-based `line_no`th line `line`, as a `T`
+pub fn parse_field<T: FromStr>(line_no: usize, line: &str, part: &str, expected: &str) -> Result<T, ParseError> {
+    part.parse()
+        .map_err(|_| ParseError::new(line_no, column(line, part), part, expected))
+}
+
+/// Split `part` of `line` in two around `separator`
+pub fn split_field<'a>(line_no: usize, line: &str, part: &'a str, separator: &str, expected: &str) -> Result<(&'a str, &'a str), ParseError> {
+    part.split_once(separator)
+        .ok_or_else(|| ParseError::new(line_no, column(line, part), part, expected))
+}
+
+/// Parse a line made up entirely of single digits, such as a row of a heightmap
+pub fn parse_digits(line_no: usize, line: &str) -> Result<Vec<u8>, ParseError> {
+    line.char_indices()
+        .map(|(j, c)| match c.to_digit(10) {
+            Some(d) => Ok(d as u8),
+            None => Err(ParseError::new(line_no, j + 1, &line[j..j + c.len_utf8()], "a digit")),
+        })
+        .collect()
+}
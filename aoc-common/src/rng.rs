@@ -0,0 +1,44 @@
+use std::ops::RangeInclusive;
+
+/// Small deterministic random number generator (xorshift64*) for
+/// generating puzzle inputs in tests. The same seed always gives the
+/// same sequence, so a failing case can be reproduced.
+#[derive(Debug, Clone)]
+pub struct Rng {
+    state: u64,
+}
+
+impl Rng {
+    pub fn new(seed: u64) -> Self {
+        // The state must never be zero
+        Rng { state: (seed ^ 0x9e37_79b9_7f4a_7c15) | 1 }
+    }
+
+    pub fn next_u64(&mut self) -> u64 {
+        self.state ^= self.state >> 12;
+        self.state ^= self.state << 25;
+        self.state ^= self.state >> 27;
+        self.state.wrapping_mul(0x2545_f491_4f6c_dd1d)
+    }
+
+    /// Uniform in `range`, which must not be empty
+    pub fn range(&mut self, range: RangeInclusive<i64>) -> i64 {
+        let (lo, hi) = range.into_inner();
+        assert!(lo <= hi, "empty range {}..={}", lo, hi);
+        let span = (hi - lo) as u64 + 1;
+        match span {
+            0 => self.next_u64() as i64,
+            _ => lo + (self.next_u64() % span) as i64,
+        }
+    }
+
+    /// Uniform in `0..n`, which must not be empty
+    pub fn below(&mut self, n: usize) -> usize {
+        assert!(n > 0, "empty range 0..0");
+        (self.next_u64() % n as u64) as usize
+    }
+
+    pub fn flip(&mut self) -> bool {
+        self.next_u64() & 1 == 1
+    }
+}
@@ -0,0 +1,87 @@
+use std::convert::TryFrom;
+use std::fmt;
+use std::io::Read;
+use std::path::Path;
+
+use crate::{read_from, read_input, Error};
+
+/// The answer to one part of a puzzle
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Answer {
+    /// Wide enough to hold any of the integer types the days produce
+    Number(i128),
+    /// Answers that are read off a rendering, like the letters in day 13
+    Text(String),
+    /// The part has not been solved yet
+    Unsolved,
+}
+
+impl fmt::Display for Answer {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Answer::Number(n) => write!(f, "{}", n),
+            Answer::Text(s) => write!(f, "{}", s),
+            Answer::Unsolved => write!(f, "unsolved"),
+        }
+    }
+}
+
+macro_rules! answer_from_int {
+    ($($t:ty),*) => {
+        $(
+            impl From<$t> for Answer {
+                fn from(n: $t) -> Self {
+                    Answer::Number(n as i128)
+                }
+            }
+        )*
+    };
+}
+
+answer_from_int!(i32, i64, u32, u64, usize);
+
+impl From<u128> for Answer {
+    /// Values past `i128::MAX` are kept as text rather than wrapping
+    fn from(n: u128) -> Self {
+        match i128::try_from(n) {
+            Ok(n) => Answer::Number(n),
+            Err(_) => Answer::Text(n.to_string()),
+        }
+    }
+}
+
+impl From<String> for Answer {
+    fn from(s: String) -> Self {
+        Answer::Text(s)
+    }
+}
+
+impl From<&str> for Answer {
+    fn from(s: &str) -> Self {
+        Answer::Text(s.to_owned())
+    }
+}
+
+/// One day of the calendar. `parse` does all the input handling once,
+/// so both parts can be run (and timed) on the same parsed puzzle.
+pub trait Solution: Sized {
+    /// Day of the month the puzzle was released on
+    const DAY: u32;
+
+    fn parse(input: &str) -> Result<Self, Error>;
+
+    /// Parse input from any reader, such as stdin
+    fn from_reader<R: Read>(reader: R) -> Result<Self, Error> {
+        Self::parse(&read_from(reader)?)
+    }
+
+    fn from_file<P: AsRef<Path>>(path: P) -> Result<Self, Error> {
+        Self::parse(&read_input(path)?)
+    }
+
+    fn part1(&self) -> Answer;
+
+    fn part2(&self) -> Answer {
+        Answer::Unsolved
+    }
+}
@@ -0,0 +1,145 @@
+use std::path::PathBuf;
+
+pub const USAGE: &str = "\
+usage: aoc run --day <N> [--part <1|2>] [--input <path, or - for stdin>]
+       aoc run --all [--part <1|2>]
+       aoc verify [--day <N>] [--registry <path>]
+       aoc bench [--day <N>] [--runs <N>] [--save <path>] [--compare <path>]";
+
+/// Which puzzles to run
+#[derive(Debug, PartialEq)]
+pub enum Selection {
+    All,
+    Day { day: u32, input: Option<PathBuf> },
+}
+
+#[derive(Debug, PartialEq)]
+pub enum Command {
+    Run { selection: Selection, part: Option<u32> },
+    /// Check answers against the registry, optionally for a single day
+    Verify { day: Option<u32>, registry: Option<PathBuf> },
+    /// Time every step of each day, optionally saving or comparing against saved results
+    Bench { day: Option<u32>, runs: Option<u32>, save: Option<PathBuf>, compare: Option<PathBuf> },
+}
+
+/// Parse the arguments following the program name
+pub fn parse_args<I: IntoIterator<Item = String>>(args: I) -> Result<Command, String> {
+    let mut args = args.into_iter();
+    let command = args.next().ok_or("missing command")?;
+    if !["run", "verify", "bench"].contains(&command.as_str()) {
+        return Err(format!("unknown command {:?}", command));
+    }
+
+    let mut all = false;
+    let mut day = None;
+    let mut part = None;
+    let mut input = None;
+    let mut registry = None;
+    let mut runs = None;
+    let mut save = None;
+    let mut compare = None;
+    while let Some(arg) = args.next() {
+        let mut value = || args.next().ok_or(format!("{} needs a value", arg));
+        match (command.as_str(), arg.as_str()) {
+            ("run", "--all") => all = true,
+            (_, "--day") => day = Some(parse_number(&value()?, "day", 1..=25)?),
+            ("run", "--part") => part = Some(parse_number(&value()?, "part", 1..=2)?),
+            ("run", "--input") => input = Some(PathBuf::from(value()?)),
+            ("verify", "--registry") => registry = Some(PathBuf::from(value()?)),
+            ("bench", "--runs") => runs = Some(parse_number(&value()?, "runs", 1..=10_000)?),
+            ("bench", "--save") => save = Some(PathBuf::from(value()?)),
+            ("bench", "--compare") => compare = Some(PathBuf::from(value()?)),
+            _ => return Err(format!("unknown argument {:?} for {}", arg, command)),
+        }
+    }
+
+    match command.as_str() {
+        "verify" => return Ok(Command::Verify { day, registry }),
+        "bench" => return Ok(Command::Bench { day, runs, save, compare }),
+        _ => (),
+    }
+    let selection = match (all, day) {
+        (true, None) if input.is_none() => Selection::All,
+        (true, None) => return Err("--input cannot be used with --all".to_owned()),
+        (true, Some(_)) => return Err("--all and --day are mutually exclusive".to_owned()),
+        (false, Some(day)) => Selection::Day { day, input },
+        (false, None) => return Err("expected --day or --all".to_owned()),
+    };
+    Ok(Command::Run { selection, part })
+}
+
+fn parse_number(value: &str, name: &str, range: std::ops::RangeInclusive<u32>) -> Result<u32, String> {
+    match value.parse() {
+        Ok(v) if range.contains(&v) => Ok(v),
+        _ => Err(format!("{} must be between {} and {}, got {:?}", name, range.start(), range.end(), value)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    fn parse(args: &str) -> Result<Command, String> {
+        parse_args(args.split_whitespace().map(|s| s.to_owned()))
+    }
+
+    #[test]
+    fn run_day() {
+        assert_eq!(
+            parse("run --day 15 --part 2 --input path"),
+            Ok(Command::Run {
+                selection: Selection::Day { day: 15, input: Some(PathBuf::from("path")) },
+                part: Some(2),
+            })
+        );
+        assert_eq!(
+            parse("run --day 3"),
+            Ok(Command::Run { selection: Selection::Day { day: 3, input: None }, part: None })
+        );
+    }
+
+    #[test]
+    fn run_all() {
+        assert_eq!(parse("run --all"), Ok(Command::Run { selection: Selection::All, part: None }));
+    }
+
+    #[test]
+    fn verify() {
+        assert_eq!(parse("verify"), Ok(Command::Verify { day: None, registry: None }));
+        assert_eq!(
+            parse("verify --day 18 --registry answers.txt"),
+            Ok(Command::Verify { day: Some(18), registry: Some(PathBuf::from("answers.txt")) })
+        );
+    }
+
+    #[test]
+    fn bench() {
+        assert_eq!(parse("bench"), Ok(Command::Bench { day: None, runs: None, save: None, compare: None }));
+        assert_eq!(
+            parse("bench --day 20 --runs 5 --save new.txt --compare old.txt"),
+            Ok(Command::Bench {
+                day: Some(20),
+                runs: Some(5),
+                save: Some(PathBuf::from("new.txt")),
+                compare: Some(PathBuf::from("old.txt")),
+            })
+        );
+    }
+
+    #[test]
+    fn invalid() {
+        assert!(parse("").is_err());
+        assert!(parse("walk --day 1").is_err());
+        assert!(parse("run").is_err());
+        assert!(parse("run --day").is_err());
+        assert!(parse("run --day 26").is_err());
+        assert!(parse("run --day 1 --part 3").is_err());
+        assert!(parse("run --all --day 1").is_err());
+        assert!(parse("run --all --input path").is_err());
+        assert!(parse("run --day 1 --verbose").is_err());
+        assert!(parse("run --day 1 --registry answers.txt").is_err());
+        assert!(parse("verify --all").is_err());
+        assert!(parse("verify --part 1").is_err());
+        assert!(parse("bench --runs 0").is_err());
+        assert!(parse("bench --input path").is_err());
+    }
+}
@@ -0,0 +1,160 @@
+use std::collections::HashMap;
+use std::fs;
+use std::path::Path;
+use std::time::Duration;
+
+use aoc_common::{parse_field, read_input, read_lines, ParseError};
+
+use crate::days::{self, Day};
+
+const DEFAULT_RUNS: u32 = 10;
+const STEPS: [&str; 3] = ["parse", "part1", "part2"];
+
+/// Summary of the timings of one step, in milliseconds
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Stats {
+    pub median: f64,
+    pub variance: f64,
+}
+
+impl Stats {
+    pub fn new(samples: &[Duration]) -> Self {
+        let mut ms: Vec<f64> = samples.iter().map(|d| d.as_secs_f64() * 1000.0).collect();
+        ms.sort_by(|a, b| a.partial_cmp(b).unwrap());
+        let n = ms.len();
+        let median = match n % 2 {
+            0 => (ms[n / 2 - 1] + ms[n / 2]) / 2.0,
+            _ => ms[n / 2],
+        };
+        let mean = ms.iter().sum::<f64>() / n as f64;
+        let variance = match n {
+            1 => 0.0,
+            _ => ms.iter().map(|v| (v - mean) * (v - mean)).sum::<f64>() / (n - 1) as f64,
+        };
+        Stats { median, variance }
+    }
+}
+
+/// Timing of one step (`parse`, `part1` or `part2`) of one day
+#[derive(Debug, PartialEq)]
+pub struct Measurement {
+    pub day: u32,
+    pub step: String,
+    pub stats: Stats,
+}
+
+/// Run a day `runs` times on its stored input, after one untimed warm-up run
+fn measure(day: &Day, runs: u32) -> Result<Vec<Measurement>, String> {
+    let path = day.default_input();
+    let input = read_input(&path).map_err(|e| format!("day {}: {}: {}", day.number, path.display(), e))?;
+    let mut samples = vec![vec![]; STEPS.len()];
+    for run in 0..=runs {
+        let outcome = (day.run)(&input, &[1, 2]).map_err(|e| format!("day {}: {}", day.number, e))?;
+        if run == 0 {
+            continue;
+        }
+        samples[0].push(outcome.parse);
+        for (i, (_, solve)) in outcome.answers.into_iter().enumerate() {
+            samples[i + 1].push(solve);
+        }
+    }
+    Ok(STEPS.iter()
+        .zip(samples)
+        .map(|(step, samples)| Measurement { day: day.number, step: step.to_string(), stats: Stats::new(&samples) })
+        .collect())
+}
+
+/// Write results in the format read back by `load`
+pub fn save(path: &Path, measurements: &[Measurement]) -> Result<(), String> {
+    let mut text = String::from("# <day> <step> <median ms> <variance ms^2>\n");
+    for m in measurements {
+        text.push_str(&format!("{} {} {} {}\n", m.day, m.step, m.stats.median, m.stats.variance));
+    }
+    fs::write(path, text).map_err(|e| format!("{}: {}", path.display(), e))
+}
+
+/// Read results written by `save`, keyed by day and step
+pub fn load(path: &Path) -> Result<HashMap<(u32, String), Stats>, String> {
+    let error = |e: ParseError| format!("{}: {}", path.display(), e);
+    let mut res = HashMap::new();
+    for (i, line) in read_lines(path).map_err(|e| format!("{}: {}", path.display(), e))?.iter().enumerate() {
+        if line.trim().is_empty() || line.starts_with('#') {
+            continue;
+        }
+        let fields: Vec<&str> = line.split_whitespace().collect();
+        if fields.len() != 4 {
+            return Err(error(ParseError::new(i + 1, 1, line, "<day> <step> <median> <variance>")));
+        }
+        let day = parse_field(i + 1, line, fields[0], "a day").map_err(error)?;
+        let median = parse_field(i + 1, line, fields[2], "a median").map_err(error)?;
+        let variance = parse_field(i + 1, line, fields[3], "a variance").map_err(error)?;
+        res.insert((day, fields[1].to_owned()), Stats { median, variance });
+    }
+    Ok(res)
+}
+
+fn report(measurements: &[Measurement], baseline: Option<&HashMap<(u32, String), Stats>>) {
+    print!("{:>3}  {:<5}  {:>12}  {:>14}", "day", "step", "median ms", "variance ms^2");
+    if baseline.is_some() {
+        print!("  {:>12}  {:>8}", "baseline ms", "change");
+    }
+    println!();
+    for m in measurements {
+        print!("{:>3}  {:<5}  {:>12.3}  {:>14.6}", m.day, m.step, m.stats.median, m.stats.variance);
+        if let Some(baseline) = baseline {
+            match baseline.get(&(m.day, m.step.clone())) {
+                Some(old) if old.median > 0.0 => {
+                    let change = (m.stats.median - old.median) / old.median * 100.0;
+                    print!("  {:>12.3}  {:>+7.1}%", old.median, change);
+                }
+                Some(old) => print!("  {:>12.3}  {:>8}", old.median, "-"),
+                None => print!("  {:>12}  {:>8}", "-", "new"),
+            }
+        }
+        println!();
+    }
+}
+
+/// `aoc bench`: time every day, print a report and optionally save it
+pub fn bench(day: Option<u32>, runs: Option<u32>, save_to: Option<&Path>, compare: Option<&Path>) -> Result<(), String> {
+    let baseline = compare.map(load).transpose()?;
+    let selected: Vec<&Day> = match day {
+        Some(number) => vec![days::find(number).ok_or(format!("day {} is not solved", number))?],
+        None => days::DAYS.iter().collect(),
+    };
+    let mut measurements = vec![];
+    for day in selected {
+        measurements.extend(measure(day, runs.unwrap_or(DEFAULT_RUNS))?);
+    }
+    report(&measurements, baseline.as_ref());
+    if let Some(path) = save_to {
+        save(path, &measurements)?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    #[test]
+    fn stats() {
+        let ms = |v: &[u64]| -> Vec<Duration> { v.iter().map(|&m| Duration::from_millis(m)).collect() };
+        assert_eq!(Stats::new(&ms(&[5])), Stats { median: 5.0, variance: 0.0 });
+        assert_eq!(Stats::new(&ms(&[9, 1, 5])), Stats { median: 5.0, variance: 16.0 });
+        assert_eq!(Stats::new(&ms(&[4, 1, 3, 2])).median, 2.5);
+    }
+
+    #[test]
+    fn save_and_load() {
+        let path = std::env::temp_dir().join("aoc-bench-test");
+        let measurements = vec![
+            Measurement { day: 1, step: "parse".to_owned(), stats: Stats { median: 0.125, variance: 0.5 } },
+            Measurement { day: 20, step: "part2".to_owned(), stats: Stats { median: 250.0, variance: 12.25 } },
+        ];
+        save(&path, &measurements).unwrap();
+        let loaded = load(&path).unwrap();
+        fs::remove_file(&path).unwrap();
+        assert_eq!(loaded.len(), 2);
+        assert_eq!(loaded[&(20, "part2".to_owned())], measurements[1].stats);
+    }
+}
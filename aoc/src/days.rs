@@ -0,0 +1,86 @@
+use std::path::PathBuf;
+use std::time::{Duration, Instant};
+
+use aoc_common::{Answer, Error, Solution};
+
+/// Answers to the requested parts, with how long each step took
+pub struct Outcome {
+    pub parse: Duration,
+    pub answers: Vec<(Answer, Duration)>,
+}
+
+/// Parse the raw input once and solve the requested parts in order
+pub type Run = fn(&str, &[u32]) -> Result<Outcome, Error>;
+
+pub struct Day {
+    pub number: u32,
+    pub run: Run,
+}
+
+/// Root of the workspace, which input paths in the registry are relative to
+pub fn workspace_dir() -> PathBuf {
+    PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("..")
+}
+
+impl Day {
+    /// The puzzle input checked in next to the day's crate
+    pub fn default_input(&self) -> PathBuf {
+        workspace_dir().join(format!("aoc{}", self.number)).join("input")
+    }
+}
+
+fn run<S: Solution>(input: &str, parts: &[u32]) -> Result<Outcome, Error> {
+    let start = Instant::now();
+    let solution = S::parse(input)?;
+    let parse = start.elapsed();
+    let answers = parts
+        .iter()
+        .map(|&part| {
+            let start = Instant::now();
+            let answer = match part {
+                1 => solution.part1(),
+                _ => solution.part2(),
+            };
+            (answer, start.elapsed())
+        })
+        .collect();
+    Ok(Outcome { parse, answers })
+}
+
+macro_rules! days {
+    ($($solution:ty),* $(,)?) => {
+        &[$(Day { number: <$solution>::DAY, run: run::<$solution> }),*]
+    };
+}
+
+pub const DAYS: &[Day] = days![
+    aoc1::Day1,
+    aoc2::Day2,
+    aoc3::Day3,
+    aoc4::Day4,
+    aoc5::Day5,
+    aoc6::Day6,
+    aoc7::Day7,
+    aoc8::Day8,
+    aoc9::Day9,
+    aoc10::Day10,
+    aoc11::Day11,
+    aoc12::Day12,
+    aoc13::Day13,
+    aoc14::Day14,
+    aoc15::Day15,
+    aoc16::Day16,
+    aoc17::Day17,
+    aoc18::Day18,
+    aoc19::Day19,
+    aoc20::Day20,
+    aoc21::Day21,
+    aoc22::Day22,
+    aoc23::Day23,
+    aoc24::Day24,
+    aoc25::Day25,
+];
+
+pub fn find(number: u32) -> Option<&'static Day> {
+    DAYS.iter().find(|d| d.number == number)
+}
@@ -0,0 +1,85 @@
+//! Command line runner for every solved day
+mod args;
+mod bench;
+mod days;
+mod verify;
+
+use std::io;
+use std::path::Path;
+use std::process;
+
+use aoc_common::{read_from, read_input, Answer};
+
+use args::{Command, Selection};
+use days::Day;
+
+fn print_answer(day: &Day, part: u32, answer: &Answer) {
+    let answer = answer.to_string();
+    if answer.contains('\n') {
+        print!("Day {} part {}:\n{}", day.number, part, answer);
+    } else {
+        println!("Day {} part {}: {}", day.number, part, answer);
+    }
+}
+
+/// `-` reads the puzzle input from stdin
+fn run_day(day: &Day, parts: &[u32], input: &Path) -> Result<(), String> {
+    let stdin = input == Path::new("-");
+    let source = if stdin { "<stdin>".into() } else { input.display().to_string() };
+    let error = |e| format!("day {}: {}: {}", day.number, source, e);
+    let input = match stdin {
+        true => read_from(io::stdin().lock()),
+        false => read_input(input),
+    }.map_err(error)?;
+    let outcome = (day.run)(&input, parts).map_err(error)?;
+    for (&part, (answer, _)) in parts.iter().zip(outcome.answers.iter()) {
+        print_answer(day, part, answer);
+    }
+    Ok(())
+}
+
+fn run(command: Command) -> Result<(), String> {
+    let (selection, part) = match command {
+        Command::Run { selection, part } => (selection, part),
+        Command::Verify { day, registry } => {
+            return match verify::verify(registry, day)? {
+                true => Ok(()),
+                false => Err("some answers did not match the registry".to_owned()),
+            };
+        }
+        Command::Bench { day, runs, save, compare } => {
+            return bench::bench(day, runs, save.as_deref(), compare.as_deref());
+        }
+    };
+    let parts = match part {
+        Some(part) => vec![part],
+        None => vec![1, 2],
+    };
+    match selection {
+        Selection::Day { day, input } => {
+            let day = days::find(day).ok_or(format!("day {} is not solved", day))?;
+            let input = input.unwrap_or_else(|| day.default_input());
+            run_day(day, &parts, &input)
+        }
+        Selection::All => {
+            for day in days::DAYS {
+                run_day(day, &parts, &day.default_input())?;
+            }
+            Ok(())
+        }
+    }
+}
+
+fn main() {
+    let command = match args::parse_args(std::env::args().skip(1)) {
+        Ok(command) => command,
+        Err(e) => {
+            eprintln!("error: {}\n{}", e, args::USAGE);
+            process::exit(2);
+        }
+    };
+    if let Err(e) = run(command) {
+        eprintln!("error: {}", e);
+        process::exit(1);
+    }
+}
@@ -0,0 +1,225 @@
+use std::panic::{self, AssertUnwindSafe};
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
+
+use aoc_common::{column, parse_field, read_input, ParseError};
+
+use crate::days;
+
+/// One known answer from the registry
+#[derive(Debug, PartialEq)]
+pub struct Case {
+    pub day: u32,
+    /// Relative to the registry file
+    pub input: PathBuf,
+    pub part: u32,
+    pub answer: String,
+}
+
+/// Result of checking one case
+#[derive(Debug, PartialEq)]
+pub enum Status {
+    Pass,
+    Mismatch { got: String },
+    /// The day could not be run at all: missing input, parse error or panic
+    Fail(String),
+}
+
+pub struct Checked {
+    pub case: Case,
+    pub status: Status,
+    pub parse: Duration,
+    pub solve: Duration,
+}
+
+/// Text answers are stored on one line in the registry
+fn escape(answer: &str) -> String {
+    answer.replace('\n', "\\n")
+}
+
+/// Parse the registry. Blank lines and lines starting with `#` are skipped.
+pub fn parse_registry(text: &str) -> Result<Vec<Case>, ParseError> {
+    let mut cases = vec![];
+    for (i, line) in text.lines().enumerate() {
+        if line.trim().is_empty() || line.trim_start().starts_with('#') {
+            continue;
+        }
+        let mut fields = line.split_whitespace();
+        let mut field = |expected: &str| {
+            fields.next().ok_or_else(|| ParseError::new(i + 1, line.len() + 1, "", expected))
+        };
+        let day = field("a day")?;
+        let input = field("an input path")?;
+        let part = field("a part")?;
+        let day = parse_field(i + 1, line, day, "a day")?;
+        let answer = line[column(line, part) - 1 + part.len()..].trim();
+        let part = match parse_field(i + 1, line, part, "part 1 or 2")? {
+            p @ 1..=2 => p,
+            _ => return Err(ParseError::new(i + 1, column(line, part), part, "part 1 or 2")),
+        };
+        if answer.is_empty() {
+            return Err(ParseError::new(i + 1, line.len() + 1, "", "an answer"));
+        }
+        cases.push(Case {
+            day,
+            input: PathBuf::from(input),
+            part,
+            answer: answer.replace("\\n", "\n"),
+        });
+    }
+    Ok(cases)
+}
+
+/// Check every part registered for one input, parsing that input only once
+fn check_input(dir: &Path, cases: Vec<Case>) -> Vec<Checked> {
+    let fail = |cases: Vec<Case>, reason: String| {
+        cases.into_iter()
+            .map(|case| Checked { case, status: Status::Fail(reason.clone()), parse: Duration::ZERO, solve: Duration::ZERO })
+            .collect()
+    };
+    let day = match days::find(cases[0].day) {
+        Some(day) => day,
+        None => {
+            let reason = format!("day {} is not solved", cases[0].day);
+            return fail(cases, reason);
+        }
+    };
+    let input = match read_input(dir.join(&cases[0].input)) {
+        Ok(input) => input,
+        Err(e) => return fail(cases, e.to_string()),
+    };
+    let parts: Vec<u32> = cases.iter().map(|case| case.part).collect();
+    let outcome = match panic::catch_unwind(AssertUnwindSafe(|| (day.run)(&input, &parts))) {
+        Ok(Ok(outcome)) => outcome,
+        Ok(Err(e)) => return fail(cases, e.to_string()),
+        Err(_) => return fail(cases, "panicked".to_owned()),
+    };
+    let parse = outcome.parse;
+    cases.into_iter()
+        .zip(outcome.answers)
+        .map(|(case, (answer, solve))| {
+            let got = answer.to_string();
+            let status = if got == case.answer { Status::Pass } else { Status::Mismatch { got } };
+            Checked { case, status, parse, solve }
+        })
+        .collect()
+}
+
+/// Run every case, optionally only those of one day, in registry order
+pub fn check(registry: &Path, day: Option<u32>) -> Result<Vec<Checked>, String> {
+    let text = read_input(registry).map_err(|e| format!("{}: {}", registry.display(), e))?;
+    let cases = parse_registry(&text).map_err(|e| format!("{}: {}", registry.display(), e))?;
+    let dir = registry.parent().unwrap_or_else(|| Path::new(""));
+
+    let mut checked = vec![];
+    let mut group: Vec<Case> = vec![];
+    for case in cases.into_iter().filter(|case| day.is_none_or(|d| d == case.day)) {
+        if let Some(last) = group.last() {
+            if last.day != case.day || last.input != case.input {
+                checked.extend(check_input(dir, std::mem::take(&mut group)));
+            }
+        }
+        group.push(case);
+    }
+    if !group.is_empty() {
+        checked.extend(check_input(dir, group));
+    }
+    Ok(checked)
+}
+
+fn millis(d: Duration) -> f64 {
+    d.as_secs_f64() * 1000.0
+}
+
+/// Print one line per case and a summary. Returns whether everything passed.
+pub fn report(checked: &[Checked], elapsed: Duration) -> bool {
+    let (mut passed, mut mismatched, mut failed) = (0, 0, 0);
+    for c in checked {
+        let (label, detail) = match &c.status {
+            Status::Pass => {
+                passed += 1;
+                ("PASS", String::new())
+            }
+            Status::Mismatch { got } => {
+                mismatched += 1;
+                ("MISMATCH", format!("  expected {}, got {}", escape(&c.case.answer), escape(got)))
+            }
+            Status::Fail(reason) => {
+                failed += 1;
+                ("FAIL", format!("  {}", reason))
+            }
+        };
+        println!(
+            "{:<8}  day {:>2} part {}  {:<20} parse {:>9.3} ms  solve {:>9.3} ms{}",
+            label,
+            c.case.day,
+            c.case.part,
+            c.case.input.display(),
+            millis(c.parse),
+            millis(c.solve),
+            detail
+        );
+    }
+    println!(
+        "{} passed, {} mismatched, {} failed in {:.2} s",
+        passed,
+        mismatched,
+        failed,
+        elapsed.as_secs_f64()
+    );
+    mismatched == 0 && failed == 0
+}
+
+/// `aoc verify`: check the registry and report
+pub fn verify(registry: Option<PathBuf>, day: Option<u32>) -> Result<bool, String> {
+    let registry = registry.unwrap_or_else(|| days::workspace_dir().join("answers.txt"));
+    let start = Instant::now();
+    let checked = check(&registry, day)?;
+    if checked.is_empty() {
+        return Err("no registered answers to check".to_owned());
+    }
+    Ok(report(&checked, start.elapsed()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    #[test]
+    fn registry_format() {
+        let text = "# comment\n\n1   aoc1/example  1 7\n13 aoc13/example 2 #.\\n.#\\n\n";
+        assert_eq!(
+            parse_registry(text),
+            Ok(vec![
+                Case { day: 1, input: PathBuf::from("aoc1/example"), part: 1, answer: "7".to_owned() },
+                Case { day: 13, input: PathBuf::from("aoc13/example"), part: 2, answer: "#.\n.#\n".to_owned() },
+            ])
+        );
+        assert_eq!(parse_registry("1 aoc1/example 3 7"), Err(ParseError::new(1, 16, "3", "part 1 or 2")));
+        assert_eq!(parse_registry("1 aoc1/example 1"), Err(ParseError::new(1, 17, "", "an answer")));
+        assert_eq!(parse_registry("x aoc1/example 1 7"), Err(ParseError::new(1, 1, "x", "a day")));
+    }
+
+    #[test]
+    fn mismatch_and_fail() {
+        let dir = days::workspace_dir();
+        let cases = vec![
+            Case { day: 1, input: PathBuf::from("aoc1/example"), part: 1, answer: "7".to_owned() },
+            Case { day: 1, input: PathBuf::from("aoc1/example"), part: 2, answer: "6".to_owned() },
+        ];
+        let statuses: Vec<Status> = check_input(&dir, cases).into_iter().map(|c| c.status).collect();
+        assert_eq!(statuses, vec![Status::Pass, Status::Mismatch { got: "5".to_owned() }]);
+
+        let cases = vec![Case { day: 1, input: PathBuf::from("aoc1/missing"), part: 1, answer: "7".to_owned() }];
+        assert!(matches!(check_input(&dir, cases)[0].status, Status::Fail(_)));
+    }
+
+    /// Every registered answer, so a regression in any day fails `cargo test`
+    #[test]
+    fn registered_answers() {
+        let checked = check(&days::workspace_dir().join("answers.txt"), None).unwrap();
+        assert!(!checked.is_empty());
+        for c in checked {
+            assert_eq!(c.status, Status::Pass, "day {} part {} on {}", c.case.day, c.case.part, c.case.input.display());
+        }
+    }
+}
@@ -1,49 +1,108 @@
-use std::io::{BufReader, Error, ErrorKind};
-use std::io::prelude::*;
-use std::fs::File;
-
-fn read_input(filename: &str) -> Result<Vec<u64>, Error> {
-    let f = File::open(filename).unwrap();
-    let f = BufReader::new(f);
-    f.lines().map(|l| l.and_then(|v| v.parse().map_err(|e| Error::new(ErrorKind::InvalidData, e)))).collect()
+use std::collections::VecDeque;
+use aoc_common::{parse_lines, Answer, Error, ParseError, Solution};
+
+mod report;
+pub use report::{Jump, Report, Run, Trend, WindowStats};
+
+/// One depth measurement per line
+pub fn parse_input(input: &str) -> Result<Vec<u64>, ParseError> {
+    parse_lines(input, "a depth measurement")
 }
 
-fn number_increases(input: &Vec<u64>) -> u64 {
-    let mut res = 0;
-    for i in 0..input.len() - 1 {
-        if input[i] < input[i + 1]
-        {
-            res += 1;
-        }
+/// Number of times the sum of a window of `k` measurements is larger than
+/// the sum of the window before it. Consecutive windows share all but their
+/// first and last measurement, so only those two need comparing.
+pub fn count_window_increases(input: &[u64], k: usize) -> u64 {
+    assert!(k >= 1, "window size must be at least 1");
+    if k >= input.len() {
+        return 0;
     }
-    res
+    input.iter().zip(input[k..].iter()).filter(|(first, last)| last > first).count() as u64
 }
 
-fn number_increases_3_window(input: &Vec<u64>) -> u64 {
+/// Same as `count_window_increases`, reading measurements one at a time and
+/// keeping only the last `k` of them
+pub fn count_window_increases_streaming<I: IntoIterator<Item = u64>>(input: I, k: usize) -> u64 {
+    assert!(k >= 1, "window size must be at least 1");
+    let mut window = VecDeque::with_capacity(k);
     let mut res = 0;
-    let mut prev = input[0] + input[1] + input[2];
-    for i in 1..input.len() - 2 {
-        let cur = prev - input[i - 1] + input[i + 2];
-        if cur > prev {
-            res += 1
+    for depth in input {
+        if window.len() == k {
+            let first = window.pop_front().unwrap();
+            if depth > first {
+                res += 1;
+            }
         }
-        prev = cur
+        window.push_back(depth);
     }
     res
 }
 
+/// Depth measurements from the sonar sweep
+pub struct Day1 {
+    depths: Vec<u64>,
+}
+
+impl Day1 {
+    /// Statistics of the sweep, with windows of `window` measurements
+    pub fn report(&self, window: usize) -> Report {
+        Report::new(&self.depths, window)
+    }
+}
+
+impl Solution for Day1 {
+    const DAY: u32 = 1;
+
+    fn parse(input: &str) -> Result<Self, Error> {
+        Ok(Day1 { depths: parse_input(input)? })
+    }
+
+    fn part1(&self) -> Answer {
+        count_window_increases(&self.depths, 1).into()
+    }
+
+    fn part2(&self) -> Answer {
+        count_window_increases(&self.depths, 3).into()
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use aoc_common::{read_parsed, Rng};
     #[test]
     fn example() {
-        assert_eq!(2 + 2, 4);
+        let input = read_parsed("example").unwrap();
+        println!("Part 1: {}", count_window_increases(&input, 1));
+        println!("Part 2: {}", count_window_increases(&input, 3));
     }
 
     #[test]
     fn actual() {
-        let input = read_input("input").unwrap();
-        println!("Part 1: {}", number_increases(&input));
-        println!("Part 2: {}", number_increases_3_window(&input));
+        let input = read_parsed("input").unwrap();
+        println!("Part 1: {}", count_window_increases(&input, 1));
+        println!("Part 2: {}", count_window_increases(&input, 3));
+    }
+
+    #[test]
+    fn window_sizes() {
+        let input = read_parsed::<u64, _>("example").unwrap();
+        assert_eq!(count_window_increases(&input, 3), 5);
+        assert_eq!(count_window_increases(&input, input.len() - 1), 1);
+        assert_eq!(count_window_increases(&input, input.len()), 0);
+        assert_eq!(count_window_increases(&input, 100), 0);
+        assert_eq!(count_window_increases(&[], 1), 0);
+        assert_eq!(count_window_increases(&input[..2], 3), 0);
+        assert_eq!(count_window_increases_streaming(vec![], 2), 0);
+    }
+
+    #[test]
+    fn streaming_matches_slice() {
+        let mut rng = Rng::new(1);
+        for _ in 0..2000 {
+            let input: Vec<u64> = (0..rng.below(30)).map(|_| rng.range(0..=20) as u64).collect();
+            let k = rng.below(35) + 1;
+            assert_eq!(count_window_increases_streaming(input.iter().copied(), k), count_window_increases(&input, k), "{:?} k = {}", input, k);
+        }
     }
 }
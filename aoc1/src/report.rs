@@ -0,0 +1,139 @@
+/// Direction of the change from one measurement to the next
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum Trend {
+    Increasing,
+    Decreasing,
+    /// Equal measurements
+    Flat,
+}
+
+/// Consecutive changes in the same direction: `len` changes covering
+/// measurements `start..=start + len`
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct Run {
+    pub trend: Trend,
+    pub start: usize,
+    pub len: usize,
+}
+
+/// Change between measurement `index` and the one after it
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct Jump {
+    pub index: usize,
+    pub from: u64,
+    pub to: u64,
+}
+
+impl Jump {
+    pub fn size(&self) -> u64 {
+        self.from.abs_diff(self.to)
+    }
+}
+
+/// Summary of the window of measurements starting at `start`
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct WindowStats {
+    pub start: usize,
+    pub min: u64,
+    pub max: u64,
+    pub mean: f64,
+}
+
+/// Statistics of a sonar sweep
+#[derive(Debug, Clone, PartialEq)]
+pub struct Report {
+    pub increases: usize,
+    pub decreases: usize,
+    /// Changes between equal measurements
+    pub plateaus: usize,
+    /// Every run in order, so together they cover the whole sweep
+    pub runs: Vec<Run>,
+    /// Largest change in either direction, the first one on ties
+    pub largest_jump: Option<Jump>,
+    pub windows: Vec<WindowStats>,
+}
+
+impl Report {
+    /// Build the report, with statistics for every window of `window` measurements
+    pub fn new(input: &[u64], window: usize) -> Self {
+        assert!(window >= 1, "window size must be at least 1");
+        let trend = |pair: &[u64]| match pair[0].cmp(&pair[1]) {
+            std::cmp::Ordering::Less => Trend::Increasing,
+            std::cmp::Ordering::Greater => Trend::Decreasing,
+            std::cmp::Ordering::Equal => Trend::Flat,
+        };
+        let mut runs: Vec<Run> = vec![];
+        let mut largest_jump: Option<Jump> = None;
+        for (i, pair) in input.windows(2).enumerate() {
+            let trend = trend(pair);
+            match runs.last_mut() {
+                Some(run) if run.trend == trend => run.len += 1,
+                _ => runs.push(Run { trend, start: i, len: 1 }),
+            }
+            let jump = Jump { index: i, from: pair[0], to: pair[1] };
+            if largest_jump.is_none_or(|j| jump.size() > j.size()) {
+                largest_jump = Some(jump);
+            }
+        }
+        let count = |trend| runs.iter().filter(|r| r.trend == trend).map(|r| r.len).sum();
+        let windows = input.windows(window)
+            .enumerate()
+            .map(|(start, w)| WindowStats {
+                start,
+                min: *w.iter().min().unwrap(),
+                max: *w.iter().max().unwrap(),
+                mean: w.iter().sum::<u64>() as f64 / w.len() as f64,
+            })
+            .collect();
+        Report {
+            increases: count(Trend::Increasing),
+            decreases: count(Trend::Decreasing),
+            plateaus: count(Trend::Flat),
+            runs,
+            largest_jump,
+            windows,
+        }
+    }
+
+    /// The longest run of `trend`, the first one on ties
+    pub fn longest(&self, trend: Trend) -> Option<&Run> {
+        self.runs.iter()
+            .filter(|r| r.trend == trend)
+            .fold(None, |best: Option<&Run>, r| match best {
+                Some(b) if b.len >= r.len => Some(b),
+                _ => Some(r),
+            })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parse_input;
+    use aoc_common::read_input;
+    #[test]
+    fn example() {
+        let input = parse_input(&read_input("example").unwrap()).unwrap();
+        let report = Report::new(&input, 3);
+        assert_eq!((report.increases, report.decreases, report.plateaus), (7, 2, 0));
+        assert_eq!(report.longest(Trend::Increasing), Some(&Run { trend: Trend::Increasing, start: 0, len: 3 }));
+        assert_eq!(report.longest(Trend::Flat), None);
+        assert_eq!(report.largest_jump, Some(Jump { index: 5, from: 207, to: 240 }));
+        assert_eq!(report.windows.len(), 8);
+        assert_eq!((report.windows[0].min, report.windows[0].max), (199, 208));
+        assert!((report.windows[0].mean - 607.0 / 3.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn plateaus() {
+        let report = Report::new(&[5, 5, 5, 3, 3, 9], 4);
+        assert_eq!((report.increases, report.decreases, report.plateaus), (1, 1, 3));
+        let trends: Vec<(Trend, usize)> = report.runs.iter().map(|r| (r.trend, r.len)).collect();
+        assert_eq!(trends, vec![(Trend::Flat, 2), (Trend::Decreasing, 1), (Trend::Flat, 1), (Trend::Increasing, 1)]);
+        assert_eq!(report.largest_jump.unwrap().size(), 6);
+        assert_eq!(report.windows.iter().map(|w| w.max).collect::<Vec<u64>>(), vec![5, 5, 9]);
+
+        let report = Report::new(&[4], 2);
+        assert!(report.runs.is_empty() && report.largest_jump.is_none() && report.windows.is_empty());
+    }
+}
@@ -1,16 +1,18 @@
-use std::io::{BufReader, Error, ErrorKind};
-use std::io::prelude::*;
-use std::fs::File;
-use std::iter::FromIterator;
 use std::collections::{HashMap, HashSet};
+use aoc_common::{lines, Answer, Error, ParseError, Solution};
 
-fn read_input(filename: &str) -> Result<Vec<String>, Error> {
-    let f = File::open(filename).unwrap();
-    let f = BufReader::new(f);
-    f.lines().map(|l| l.and_then(|v| v.parse().map_err(|e| Error::new(ErrorKind::InvalidData, e)))).collect()
+/// Check that every line is made up of brackets only
+pub fn validate(input: &[String]) -> Result<(), ParseError> {
+    for (i, line) in input.iter().enumerate() {
+        if let Some(col) = line.find(|c| !"()[]{}<>".contains(c)) {
+            let len = line[col..].chars().next().unwrap().len_utf8();
+            return Err(ParseError::new(i + 1, col + 1, &line[col..col + len], "one of ()[]{}<>"));
+        }
+    }
+    Ok(())
 }
 
-fn get_score(input: &Vec<String>, part1: bool) -> u64 {
+pub fn get_score(input: &[String], part1: bool) -> u64 {
     let mut matches = HashMap::new();
     matches.insert(')', '(');
     matches.insert(']', '[');
@@ -53,14 +55,14 @@ fn get_score(input: &Vec<String>, part1: bool) -> u64 {
             }
         }
         match part1 {
-            true => return accum,
+            true => accum,
             false => {
                 let mut res = 0;
                 while !stack.is_empty() {
                     res = res * 5 + scores2[&stack.pop().unwrap()];
                 }
                 values.push(res);
-                return accum + res;
+                accum + res
             }
         }
     });
@@ -71,18 +73,42 @@ fn get_score(input: &Vec<String>, part1: bool) -> u64 {
     values[values.len() / 2]
 }
 
+/// Lines of the navigation subsystem
+pub struct Day10 {
+    lines: Vec<String>,
+}
+
+impl Solution for Day10 {
+    const DAY: u32 = 10;
+
+    fn parse(input: &str) -> Result<Self, Error> {
+        let lines = lines(input);
+        validate(&lines)?;
+        Ok(Day10 { lines })
+    }
+
+    fn part1(&self) -> Answer {
+        get_score(&self.lines, true).into()
+    }
+
+    fn part2(&self) -> Answer {
+        get_score(&self.lines, false).into()
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use aoc_common::read_lines;
     #[test]
     fn example() {
-        let input = read_input("example").unwrap();
+        let input = read_lines("example").unwrap();
         println!("Part1: {}", get_score(&input, true));
         println!("Part2: {}", get_score(&input, false));
     }
     #[test]
     fn actual() {
-        let input = read_input("input").unwrap();
+        let input = read_lines("input").unwrap();
         println!("Part1: {}", get_score(&input, true));
         println!("Part2: {}", get_score(&input, false));
     }
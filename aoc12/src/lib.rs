@@ -1,16 +1,8 @@
-use std::io::{BufReader, Error, ErrorKind};
-use std::io::prelude::*;
-use std::fs::File;
-use std::iter::FromIterator;
 use std::collections::{HashMap, HashSet};
 use bimap::BiMap;
+use aoc_common::{lines, split_field, Answer, Error, ParseError, Solution};
 
-fn read_input(filename: &str) -> Result<Vec<String>, Error> {
-    let f = File::open(filename).unwrap();
-    let f = BufReader::new(f);
-    f.lines().map(|l| l.and_then(|v| v.parse().map_err(|e| Error::new(ErrorKind::InvalidData, e)))).collect()
-}
-
+#[derive(Clone)]
 pub struct Graph {
     node_mapping: BiMap<String, usize>,
     capital_mapping: Vec<bool>,
@@ -19,13 +11,14 @@ pub struct Graph {
 }
 
 impl Graph {
-    pub fn new(input: &Vec<String>, part2: bool) -> Self {
+    pub fn new(input: &[String], part2: bool) -> Result<Self, ParseError> {
         let mut node_num = 0;
         let mut node_mapping = BiMap::new();
         let mut capital_mapping = Vec::new();
         let mut graph = HashMap::new();
-        for val in input.iter() {
-            let edge: Vec<&str> = val.split("-").collect();
+        for (i, val) in input.iter().enumerate() {
+            let (from, to) = split_field(i + 1, val, val, "-", "two caves joined by -")?;
+            let edge = [from, to];
             let edge_0_map = match node_mapping.contains_left(edge[0]) {
                 true => *node_mapping.get_by_left(edge[0]).unwrap(),
                 false => {
@@ -49,15 +42,20 @@ impl Graph {
             let ch = graph.entry(edge_1_map).or_insert(HashSet::new());
             (*ch).insert(edge_0_map);
         }
-        Graph {
-            node_mapping: node_mapping,
-            capital_mapping: capital_mapping,
-            graph: graph,
-            part2: part2,
+        for cave in ["start", "end"].iter() {
+            if !node_mapping.contains_left(*cave) {
+                return Err(ParseError::new(input.len() + 1, 1, "", &format!("a path from the {} cave", cave)));
+            }
         }
+        Ok(Graph {
+            node_mapping,
+            capital_mapping,
+            graph,
+            part2,
+        })
     }
 
-    fn flip_part2(&mut self) {
+    pub fn flip_part2(&mut self) {
         self.part2 = !self.part2;
     }
 
@@ -116,13 +114,37 @@ impl Graph {
     }
 }
 
+/// Map of the cave system
+pub struct Day12 {
+    graph: Graph,
+}
+
+impl Solution for Day12 {
+    const DAY: u32 = 12;
+
+    fn parse(input: &str) -> Result<Self, Error> {
+        Ok(Day12 { graph: Graph::new(&lines(input), false)? })
+    }
+
+    fn part1(&self) -> Answer {
+        self.graph.distinct_paths().into()
+    }
+
+    fn part2(&self) -> Answer {
+        let mut graph = self.graph.clone();
+        graph.flip_part2();
+        graph.distinct_paths().into()
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use aoc_common::read_lines;
     #[test]
     fn example1() {
-        let input = read_input("example1").unwrap();
-        let mut graph = Graph::new(&input, false);
+        let input = read_lines("example1").unwrap();
+        let mut graph = Graph::new(&input, false).unwrap();
         println!("Part1: {}", graph.distinct_paths());
         graph.flip_part2();
         println!("Part2: {}", graph.distinct_paths());
@@ -130,8 +152,8 @@ mod tests {
 
     #[test]
     fn example2() {
-        let input = read_input("example2").unwrap();
-        let mut graph = Graph::new(&input, false);
+        let input = read_lines("example2").unwrap();
+        let mut graph = Graph::new(&input, false).unwrap();
         println!("Part1: {}", graph.distinct_paths());
         graph.flip_part2();
         println!("Part2: {}", graph.distinct_paths());
@@ -139,8 +161,8 @@ mod tests {
 
     #[test]
     fn example3() {
-        let input = read_input("example3").unwrap();
-        let mut graph = Graph::new(&input, false);
+        let input = read_lines("example3").unwrap();
+        let mut graph = Graph::new(&input, false).unwrap();
         println!("Part1: {}", graph.distinct_paths());
         graph.flip_part2();
         println!("Part2: {}", graph.distinct_paths());
@@ -148,8 +170,8 @@ mod tests {
 
     #[test]
     fn actual() {
-        let input = read_input("input").unwrap();
-        let mut graph = Graph::new(&input, false);
+        let input = read_lines("input").unwrap();
+        let mut graph = Graph::new(&input, false).unwrap();
         println!("Part1: {}", graph.distinct_paths());
         graph.flip_part2();
         println!("Part2: {}", graph.distinct_paths());
@@ -1,30 +1,26 @@
-use std::io::{BufReader, Error, ErrorKind};
-use std::io::prelude::*;
-use std::fs::File;
-use std::iter::FromIterator;
 use std::cmp::max;
+use aoc_common::{column, lines, parse_field, split_field, Answer, Error, Grid, ParseError, Solution};
 
-fn read_input(filename: &str) -> Result<Vec<String>, Error> {
-    let f = File::open(filename).unwrap();
-    let f = BufReader::new(f);
-    f.lines().map(|l| l.and_then(|v| v.parse().map_err(|e| Error::new(ErrorKind::InvalidData, e)))).collect()
-}
+/// Fold line as (axis, position), where axis 0 folds along y and 1 along x
+pub type Fold = (usize, usize);
 
 // Reverse x and y to resemble matrix convention - first coordinate is row and second coordinate is column
-fn parse_input(input: &Vec<String>) -> (Vec<Vec<bool>>, Vec<(usize, usize)>) {
+pub fn parse_input(input: &[String]) -> Result<(Grid<bool>, Vec<Fold>), ParseError> {
     let mut flag = false;
     let mut coords = vec![];
     let mut x_max = 0;
     let mut y_max = 0;
     let mut folds = vec![];
-    for val in input.iter() {
+    for (i, val) in input.iter().enumerate() {
         let s = val.trim();
         if flag {
-            let ss:Vec<&str> = s.split(" ").last().unwrap().split("=").collect();
-            match ss[0] {
-                "y" => folds.push((0, ss[1].parse::<usize>().unwrap())),
-                "x" => folds.push((1, ss[1].parse::<usize>().unwrap())),
-                _ => panic!("unknown input"),
+            let instruction = s.rsplit(' ').next().unwrap();
+            let (axis, line) = split_field(i + 1, val, instruction, "=", "fold along x=N or y=N")?;
+            let line = parse_field::<usize>(i + 1, val, line, "a number")?;
+            match axis {
+                "y" => folds.push((0, line)),
+                "x" => folds.push((1, line)),
+                _ => return Err(ParseError::new(i + 1, column(val, axis), axis, "x or y")),
             };
             continue;
         }
@@ -32,84 +28,112 @@ fn parse_input(input: &Vec<String>) -> (Vec<Vec<bool>>, Vec<(usize, usize)>) {
             flag = true;
             continue;
         }
-        let ss:Vec<&str> = s.split(",").collect();
-        let ss0 = ss[0].parse::<usize>().unwrap();
-        let ss1 = ss[1].parse::<usize>().unwrap();
+        let (x, y) = split_field(i + 1, val, s, ",", "x,y")?;
+        let ss0 = parse_field::<usize>(i + 1, val, x, "a number")?;
+        let ss1 = parse_field::<usize>(i + 1, val, y, "a number")?;
         x_max = max(x_max, ss1);
         y_max = max(y_max, ss0);
         coords.push((ss1, ss0));
     }
-    let mut matrix = vec![vec![false; y_max + 1]; x_max + 1];
+    let mut matrix = Grid::new(y_max + 1, x_max + 1, false);
+    if folds.is_empty() {
+        return Err(ParseError::new(input.len() + 1, 1, "", "a fold instruction"));
+    }
     for val in coords {
-        matrix[val.0][val.1] = true;
+        matrix[val] = true;
     }
-    (matrix, folds)
+    Ok((matrix, folds))
 }
 
-fn fold_along_line(input: &mut Vec<Vec<bool>>, fold: (usize, usize)) {
+/// Fold the bottom half up (axis 0) or the right half left (axis 1)
+pub fn fold_along_line(input: &Grid<bool>, fold: Fold) -> Grid<bool> {
+    let mirror = |i: usize| 2 * fold.1 - i;
     if fold.0 == 0 {
-        for i in 1..(input.len() - fold.1) {
-            for j in 0..input[0].len() {
-                input[fold.1 - i][j] = input[fold.1 - i][j] | input[fold.1 + i][j];
-            }
-        }
-        input.truncate(fold.1);
+        Grid::from_fn(input.width(), fold.1, |(i, j)| {
+            input[(i, j)] || input.get((mirror(i), j)).copied().unwrap_or(false)
+        })
     }
     else {
-        for i in 0..input.len() {
-            for j in 1..(input[0].len() - fold.1) {
-                input[i][fold.1 - j] = input[i][fold.1 - j] | input[i][fold.1 + j];
-            }
-        }
-        for i in 0..input.len() {
-            input[i].truncate(fold.1);
-        }
+        Grid::from_fn(fold.1, input.height(), |(i, j)| {
+            input[(i, j)] || input.get((i, mirror(j))).copied().unwrap_or(false)
+        })
     }
 }
 
-fn count_points(input: &Vec<Vec<bool>>) -> usize {
-    input.iter().flatten().map(|v| match v {
-        true => 1,
-        false => 0
-    }).sum()
+pub fn count_points(input: &Grid<bool>) -> usize {
+    input.iter().filter(|&&v| v).count()
+}
+
+/// Draw the sheet with `#` for dots and `.` for empty space
+pub fn pretty_print(input: &Grid<bool>) {
+    print!("{}", input);
+}
+
+/// Transparent paper dots and the fold instructions
+pub struct Day13 {
+    matrix: Grid<bool>,
+    folds: Vec<Fold>,
 }
 
-fn pretty_print(input: &Vec<Vec<bool>>) {
-    for i in 0..input.len() {
-        for j in 0..input[0].len() {
-            match input[i][j] {
-                true => print!("x"),
-                false => print!(" "),
-            }
+impl Solution for Day13 {
+    const DAY: u32 = 13;
+
+    fn parse(input: &str) -> Result<Self, Error> {
+        let (matrix, folds) = parse_input(&lines(input))?;
+        Ok(Day13 { matrix, folds })
+    }
+
+    fn part1(&self) -> Answer {
+        count_points(&fold_along_line(&self.matrix, self.folds[0])).into()
+    }
+
+    fn part2(&self) -> Answer {
+        let mut matrix = self.matrix.clone();
+        for fold in self.folds.iter() {
+            matrix = fold_along_line(&matrix, *fold);
         }
-        println!("");
+        matrix.to_string().into()
     }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use aoc_common::read_lines;
     #[test]
     fn example() {
-        let input = read_input("example").unwrap();
-        let (mut matrix, folds) = parse_input(&input);
-        fold_along_line(&mut matrix, folds[0]);
+        let input = read_lines("example").unwrap();
+        let (mut matrix, folds) = parse_input(&input).unwrap();
+        matrix = fold_along_line(&matrix, folds[0]);
         println!("Part1: {}", count_points(&matrix));
-        for i in 1..folds.len() {
-            fold_along_line(&mut matrix, folds[i]);
+        for fold in folds.iter().skip(1) {
+            matrix = fold_along_line(&matrix, *fold);
         }
         //pretty_print(&matrix);
     }
 
     #[test]
     fn actual() {
-        let input = read_input("input").unwrap();
-        let (mut matrix, folds) = parse_input(&input);
-        fold_along_line(&mut matrix, folds[0]);
+        let input = read_lines("input").unwrap();
+        let (mut matrix, folds) = parse_input(&input).unwrap();
+        matrix = fold_along_line(&matrix, folds[0]);
         println!("Part1: {}", count_points(&matrix));
-        for i in 1..folds.len() {
-            fold_along_line(&mut matrix, folds[i]);
+        for fold in folds.iter().skip(1) {
+            matrix = fold_along_line(&matrix, *fold);
         }
         pretty_print(&matrix);
     }
+
+    #[test]
+    fn bad_input() {
+        let mut input = read_lines("example").unwrap();
+        input[2] = "6 10".to_owned();
+        assert_eq!(parse_input(&input), Err(ParseError::new(3, 1, "6 10", "x,y")));
+        let mut input = read_lines("example").unwrap();
+        let last = input.len() - 1;
+        input[last] = "fold along z=5".to_owned();
+        assert_eq!(parse_input(&input), Err(ParseError::new(last + 1, 12, "z", "x or y")));
+        input.truncate(last - 1);
+        assert_eq!(parse_input(&input), Err(ParseError::new(last, 1, "", "a fold instruction")));
+    }
 }
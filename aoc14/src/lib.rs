@@ -1,47 +1,44 @@
-use std::io::{BufReader, Error, ErrorKind};
-use std::io::prelude::*;
-use std::fs::File;
-use std::iter::FromIterator;
 use std::collections::HashMap;
+use aoc_common::{column, lines, split_field, Answer, Error, ParseError, Solution};
 
-fn read_input(filename: &str) -> Result<Vec<String>, Error> {
-    let f = File::open(filename).unwrap();
-    let f = BufReader::new(f);
-    f.lines().map(|l| l.and_then(|v| v.parse().map_err(|e| Error::new(ErrorKind::InvalidData, e)))).collect()
-}
-
-fn parse_input(input: Vec<String>) -> HashMap<String, String> {
+/// Split the puzzle into the polymer template (one element per entry) and the insertion rules
+pub fn parse_input(input: Vec<String>) -> Result<(Vec<String>, HashMap<String, String>), ParseError> {
+    let template: Vec<String> = match input.first() {
+        Some(first) if !first.is_empty() => first.chars().map(|v| v.to_string()).collect(),
+        _ => return Err(ParseError::new(1, 1, "", "a polymer template")),
+    };
     let mut rules = HashMap::new();
-    for val in input.iter() {
-        let ss: Vec<&str> = val.split("->").collect();
-        rules.insert(ss[0].trim().to_owned(), ss[1].trim().to_owned());
+    for (i, val) in input.iter().enumerate().skip(2) {
+        let (pair, element) = split_field(i + 1, val, val, "->", "a rule like AB -> C")?;
+        let (pair, element) = (pair.trim(), element.trim());
+        if pair.chars().count() != 2 {
+            return Err(ParseError::new(i + 1, column(val, pair), pair, "a pair of elements"));
+        }
+        if element.chars().count() != 1 {
+            return Err(ParseError::new(i + 1, column(val, element), element, "a single element"));
+        }
+        rules.insert(pair.to_owned(), element.to_owned());
     }
-    rules
+    Ok((template, rules))
 }
 
-fn brute_force(template: &Vec<String>, rules: &HashMap<String, String>, num_steps: usize) -> Vec<String> {
-    let mut old_vec = template.clone();
+pub fn brute_force(template: &[String], rules: &HashMap<String, String>, num_steps: usize) -> Vec<String> {
+    let mut old_vec = template.to_vec();
     for _ in 0..num_steps {
         let mut new_vec = Vec::new();
         new_vec.push(old_vec[0].to_owned());
-        let mut window = old_vec.windows(2);
-        loop {
-            match window.next() {
-                Some(v) => {
-                  let val = v.join(&"");
-                  new_vec.push(rules[&val].to_owned());
-                  new_vec.push(v[1].to_owned());  
-                },
-                None => break,
-            };
+        for v in old_vec.windows(2) {
+            let val = v.join("");
+            new_vec.push(rules[&val].to_owned());
+            new_vec.push(v[1].to_owned());
         }
         old_vec = new_vec.clone();
     }
     old_vec
 }
 
-fn optimized(template: &Vec<String>, rules: &HashMap<String, String>, num_steps: usize) -> HashMap<String, u64> {
-    let pairs: Vec<String> = template.windows(2).map(|v| v.join(&"")).collect();
+pub fn optimized(template: &[String], rules: &HashMap<String, String>, num_steps: usize) -> HashMap<String, u64> {
+    let pairs: Vec<String> = template.windows(2).map(|v| v.join("")).collect();
     let mut old_map = HashMap::new();
     for val in pairs {
         let ch = old_map.entry(val.to_string()).or_insert(0);
@@ -53,7 +50,7 @@ fn optimized(template: &Vec<String>, rules: &HashMap<String, String>, num_steps:
             let ss: Vec<char> = k.chars().collect();
             let insertion = &rules[k][..];
             let mut pair_1: String = ss[0].to_string();
-            pair_1.push_str(&insertion);
+            pair_1.push_str(insertion);
             let mut pair_2: String = insertion.to_string();
             pair_2.push_str(&ss[1].to_string()[..]);
 
@@ -68,7 +65,7 @@ fn optimized(template: &Vec<String>, rules: &HashMap<String, String>, num_steps:
     old_map
 }
 
-fn count_range(input: &HashMap<String, u64>, first: String, last: String) -> u64 {
+pub fn count_range(input: &HashMap<String, u64>, first: String, last: String) -> u64 {
     let mut counter = HashMap::new();
     for (k, v) in input.iter() {
         let ss: Vec<char> = k.chars().collect();
@@ -77,7 +74,7 @@ fn count_range(input: &HashMap<String, u64>, first: String, last: String) -> u64
         let v2 = counter.entry(ss[1].to_string()).or_insert(0);
         *v2 += v;
     }
-    for (k, v) in counter.iter_mut() {
+    for v in counter.values_mut() {
         *v /= 2;
     }
     let v = counter.entry(first).or_insert(0);
@@ -98,13 +95,43 @@ fn count_range(input: &HashMap<String, u64>, first: String, last: String) -> u64
     max_val - min_val
 }
 
+/// Polymer template and pair insertion rules
+pub struct Day14 {
+    template: Vec<String>,
+    rules: HashMap<String, String>,
+}
+
+impl Day14 {
+    fn solve(&self, num_steps: usize) -> u64 {
+        let output = optimized(&self.template, &self.rules, num_steps);
+        count_range(&output, self.template[0].to_owned(), self.template[self.template.len() - 1].to_owned())
+    }
+}
+
+impl Solution for Day14 {
+    const DAY: u32 = 14;
+
+    fn parse(input: &str) -> Result<Self, Error> {
+        let (template, rules) = parse_input(lines(input))?;
+        Ok(Day14 { template, rules })
+    }
+
+    fn part1(&self) -> Answer {
+        self.solve(10).into()
+    }
+
+    fn part2(&self) -> Answer {
+        self.solve(40).into()
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use aoc_common::{read_lines, Rng};
     #[test]
     fn example() {
-        let template = "NNCB".chars().map(|v| v.to_string()).collect();
-        let rules = parse_input(read_input("example").unwrap());
+        let (template, rules) = parse_input(read_lines("example").unwrap()).unwrap();
         //let output = brute_force(&template, &rules, 10);
         let output = optimized(&template, &rules, 10);
         println!("Part1: {}", count_range(&output, template[0].to_owned(), template[template.len() - 1].to_owned()));
@@ -114,12 +141,39 @@ mod tests {
 
     #[test]
     fn actual() {
-        let template: Vec<String> = "PHVCVBFHCVPFKBNHKNBO".chars().map(|v| v.to_string()).collect();
-        let rules = parse_input(read_input("input").unwrap());
+        let (template, rules) = parse_input(read_lines("input").unwrap()).unwrap();
         //let output = brute_force(&template, &rules, 10);
         let output = optimized(&template, &rules, 10);
         println!("Part1: {}", count_range(&output, template[0].to_owned(), template[template.len() - 1].to_owned()));
         let output = optimized(&template, &rules, 40);
         println!("Part1: {}", count_range(&output, template[0].to_owned(), template[template.len() - 1].to_owned()));
     }
+
+    /// Random template over a small alphabet, with a rule for every pair
+    fn random_polymer(rng: &mut Rng) -> (Vec<String>, HashMap<String, String>) {
+        let alphabet: Vec<String> = "ABCD"[..rng.range(1..=4) as usize].chars().map(|c| c.to_string()).collect();
+        let template = (0..rng.range(2..=8)).map(|_| alphabet[rng.below(alphabet.len())].clone()).collect();
+        let mut rules = HashMap::new();
+        for a in alphabet.iter() {
+            for b in alphabet.iter() {
+                rules.insert(format!("{}{}", a, b), alphabet[rng.below(alphabet.len())].clone());
+            }
+        }
+        (template, rules)
+    }
+
+    #[test]
+    fn brute_force_matches_optimized() {
+        let mut rng = Rng::new(14);
+        for _ in 0..2000 {
+            let (template, rules) = random_polymer(&mut rng);
+            let steps = rng.below(7);
+            let polymer = brute_force(&template, &rules, steps);
+            let mut pairs = HashMap::new();
+            for v in polymer.windows(2) {
+                *pairs.entry(v.join("")).or_insert(0) += 1;
+            }
+            assert_eq!(pairs, optimized(&template, &rules, steps), "{:?} {:?} after {} steps", template, rules, steps);
+        }
+    }
 }
@@ -1,117 +1,94 @@
-use std::io::{BufReader, Error, ErrorKind};
-use std::io::prelude::*;
-use std::fs::File;
-use std::iter::FromIterator;
-use std::cmp::min;
 use std::collections::{VecDeque, BinaryHeap};
+use aoc_common::{lines, Answer, Error, Grid, ParseError, Pos, Solution};
 
-fn read_input(filename: &str) -> Result<Vec<String>, Error> {
-    let f = File::open(filename).unwrap();
-    let f = BufReader::new(f);
-    f.lines().map(|l| l.and_then(|v| v.parse().map_err(|e| Error::new(ErrorKind::InvalidData, e)))).collect()
+pub fn parse_input(input: &[String]) -> Result<Grid<u8>, ParseError> {
+    Grid::from_lines(input)
 }
 
-fn parse_input(input: &Vec<String>) -> Vec<Vec<i32>> {
-    input.iter().map(|row| row.chars().map(|c| c.to_digit(10).unwrap() as i32).collect()).collect()
+fn corner(grid: &Grid<u8>) -> Pos {
+    (grid.height() - 1, grid.width() - 1)
 }
 
-fn dijkstra(grid: &Vec<Vec<i32>>) -> i32 {
-    let mut min_cost = vec![vec![i32::MAX; grid[0].len()]; grid.len()];
-    min_cost[0][0] = 0;
-
-    let neighbors = |i, j| {
-        let mut res = vec![];
-        if i < grid.len() - 1 {
-            res.push((i + 1, j));
-        }
-        if i > 0 {
-            res.push((i - 1, j));
-        }
-        if j > 0 {
-            res.push((i, j - 1));
-        }
-        if j < grid[0].len() - 1 {
-            res.push((i, j + 1));
-        }
-        res
-    };
+pub fn dijkstra(grid: &Grid<u8>) -> i32 {
+    let mut min_cost = Grid::new(grid.width(), grid.height(), i32::MAX);
+    min_cost[(0, 0)] = 0;
 
     let mut heap = BinaryHeap::new();
     heap.push((0, (0, 0)));
     
     while !heap.is_empty() {
         let (cost, coord) = heap.pop().unwrap();
-        let cost: i32 = cost * -1;
-        let (i, j) = coord;
+        let cost: i32 = -cost;
 
-        for (x, y) in neighbors(i, j) {
-            if cost + grid[x][y] < min_cost[x][y] {
-                min_cost[x][y] = cost + grid[x][y];
-                heap.push((-1 * min_cost[x][y], (x, y)));
+        for next in grid.neighbors4(coord) {
+            if cost + (grid[next] as i32) < min_cost[next] {
+                min_cost[next] = cost + grid[next] as i32;
+                heap.push((-min_cost[next], next));
             }
         }
     }
-    min_cost[grid.len() - 1][grid[0].len() - 1]
+    min_cost[corner(grid)]
 }
 
-fn bfs(grid: &Vec<Vec<i32>>) -> i32 {
-    let mut min_cost = vec![vec![i32::MAX; grid[0].len()]; grid.len()];
-    min_cost[0][0] = 0;
+pub fn bfs(grid: &Grid<u8>) -> i32 {
+    let mut min_cost = Grid::new(grid.width(), grid.height(), i32::MAX);
+    min_cost[(0, 0)] = 0;
     let mut q = VecDeque::new();
     q.push_back(((0, 1), 0)); // Coordinate and current cost
     q.push_back(((1, 0), 0));
-    let neighbors = |i, j| {
-        let mut res = vec![];
-        if i < grid.len() - 1 {
-            res.push((i + 1, j));
-        }
-        if i > 0 {
-            res.push((i - 1, j));
-        }
-        if j > 0 {
-            res.push((i, j - 1));
-        }
-        if j < grid[0].len() - 1 {
-            res.push((i, j + 1));
-        }
-        res
-    };
     while !q.is_empty() {
         let (coord, cost) = q.pop_front().unwrap();
-        let (i, j) = coord;
-        if cost + grid[i][j] >= min_cost[i][j] {
+        if !grid.contains(coord) || cost + (grid[coord] as i32) >= min_cost[coord] {
             continue;
         }
-        min_cost[i][j] = cost + grid[i][j];
-        for (x, y) in neighbors(i, j) {
-            q.push_back(((x, y), min_cost[i][j]));
+        min_cost[coord] = cost + grid[coord] as i32;
+        for next in grid.neighbors4(coord) {
+            q.push_back((next, min_cost[coord]));
         }
     }
-    min_cost[grid.len() - 1][grid[0].len() - 1]
+    min_cost[corner(grid)]
 }
 
-fn get_tiles(grid: &Vec<Vec<i32>>) -> Vec<Vec<i32>> {
-    let M = grid.len();
-    let N = grid[0].len();
-    let mut new_grid = vec![vec![0; N * 5]; M * 5];
-    for i in 0..new_grid.len() {
-        for j in 0..new_grid[0].len() {
-            let mut val = grid[i % M][j % N] + (i / M) as i32 + (j / N) as i32;
-            if val >= 10 {
-                val -= 9;
-            }
-            new_grid[i][j] = val;
+pub fn get_tiles(grid: &Grid<u8>) -> Grid<u8> {
+    let m = grid.height();
+    let n = grid.width();
+    Grid::from_fn(n * 5, m * 5, |(i, j)| {
+        let val = grid[(i % m, j % n)] + (i / m) as u8 + (j / n) as u8;
+        match val >= 10 {
+            true => val - 9,
+            false => val,
         }
+    })
+}
+
+/// Risk level of every position in the cave
+pub struct Day15 {
+    grid: Grid<u8>,
+}
+
+impl Solution for Day15 {
+    const DAY: u32 = 15;
+
+    fn parse(input: &str) -> Result<Self, Error> {
+        Ok(Day15 { grid: parse_input(&lines(input))? })
+    }
+
+    fn part1(&self) -> Answer {
+        dijkstra(&self.grid).into()
+    }
+
+    fn part2(&self) -> Answer {
+        dijkstra(&get_tiles(&self.grid)).into()
     }
-    new_grid
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use aoc_common::{read_lines, Rng};
     #[test]
     fn example() {
-        let grid = parse_input(&read_input("example").unwrap());
+        let grid = parse_input(&read_lines("example").unwrap()).unwrap();
         println!("Part1: {}", bfs(&grid));
         let new_grid = get_tiles(&grid);
         //println!("Part2: {}", bfs(&new_grid));
@@ -120,10 +97,25 @@ mod tests {
 
     #[test]
     fn actual() {
-        let grid = parse_input(&read_input("input").unwrap());
+        let grid = parse_input(&read_lines("input").unwrap()).unwrap();
         println!("Part1: {}", bfs(&grid));
         let new_grid = get_tiles(&grid);
         //println!("Part2: {}", bfs(&new_grid));
         println!("Part2 Dijkstra: {}", dijkstra(&new_grid));
     }
+
+    /// Risk levels 1 to 9 on a small cave
+    fn random_cave(rng: &mut Rng) -> Grid<u8> {
+        let (width, height) = (rng.below(12) + 1, rng.below(12) + 1);
+        Grid::from_fn(width, height, |_| rng.range(1..=9) as u8)
+    }
+
+    #[test]
+    fn dijkstra_matches_bfs() {
+        let mut rng = Rng::new(15);
+        for _ in 0..2000 {
+            let cave = random_cave(&mut rng);
+            assert_eq!(dijkstra(&cave), bfs(&cave), "\n{}", cave);
+        }
+    }
 }
@@ -1,130 +1,157 @@
-use std::io::{BufReader, Error, ErrorKind};
-use std::io::prelude::*;
-use std::fs::File;
-use std::iter::FromIterator;
-
-fn read_input(filename: &str) -> Result<Vec<String>, Error> {
-    let f = File::open(filename).unwrap();
-    let f = BufReader::new(f);
-    f.lines().map(|l| l.and_then(|v| v.parse().map_err(|e| Error::new(ErrorKind::InvalidData, e)))).collect()
-}
+use aoc_common::{Answer, Error, ParseError, Solution};
 
-fn get_binary(input: &String) -> Vec<u8> {
+/// Expand the hexadecimal transmission into one bit per entry
+pub fn get_binary(input: &str) -> Result<Vec<u8>, ParseError> {
+    if input.is_empty() {
+        return Err(ParseError::new(1, 1, "", "a hexadecimal transmission"));
+    }
     let mut string = "".to_string();
-    for v in input.chars() {
-        string.push_str(&format!("{:04b}", u32::from_str_radix(&v.to_string(), 16).unwrap()));
+    for (j, v) in input.char_indices() {
+        let digit = v.to_digit(16)
+            .ok_or_else(|| ParseError::new(1, j + 1, &input[j..j + v.len_utf8()], "a hexadecimal digit"))?;
+        string.push_str(&format!("{:04b}", digit));
     }
-    string.chars().map(|v| match v {
-        '0' => 0,
-        '1' => 1,
-        _ => panic!("not binary"),
-    }).collect()
+    Ok(string.bytes().map(|v| v - b'0').collect())
 }
 
-fn binary_to_decimal(binary: &[u8]) -> usize {
-    let mut res: usize = 0;
-    for v in binary {
-        res = (res << 1) | (*v as usize);
-    }
-    res.into()
+/// One packet of the transmission
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Packet {
+    pub version: usize,
+    pub contents: Contents,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Contents {
+    Literal(usize),
+    Operator { type_id: usize, subpackets: Vec<Packet> },
 }
 
-// Return version and result
-fn control_loop(packet: &Vec<u8>) -> (usize, usize) {
-    let (ind, version, result) = process_packet(packet, 0);
-    (version, result)
+/// Reads bits in order, reporting a read past the end at the hex digit it starts in
+struct Reader<'a> {
+    line: &'a str,
+    bits: Vec<u8>,
+    pos: usize,
 }
 
-// Process packet starting at start_index: Return end_index, version of the packet, and result of operation
-fn process_packet(packet: &Vec<u8>, start_index: usize) -> (usize, usize, usize) {
-    let mut cur_index = start_index;
-    let mut version = binary_to_decimal(&packet[cur_index..(cur_index+3)]);
-    cur_index += 3;
-    let type_id = binary_to_decimal(&packet[cur_index..(cur_index+3)]);
-    cur_index += 3;
-    // Literal value
-    if type_id == 4 {
-        let (ind, number) = process_literal(packet, cur_index);
-        cur_index = ind;
-        return (cur_index, version, number);
+impl Reader<'_> {
+    fn error_at(&self, pos: usize, expected: &str) -> ParseError {
+        let j = (pos / 4).min(self.line.len());
+        ParseError::new(1, j + 1, self.line.get(j..j + 1).unwrap_or(""), expected)
     }
-    let length_type_id = packet[cur_index];
-    cur_index += 1;
-
-    let mut subpackets = vec![];
-    // Length Type Id = 0
-    if length_type_id == 0 {
-        let subpacket_length = binary_to_decimal(&packet[cur_index..(cur_index + 15)]);
-        cur_index += 15;
-        let orig_index = cur_index;
-        loop {
-            let (ind, ver, number) = process_packet(packet, cur_index);
-            subpackets.push(number);
-            version += ver;
-            cur_index = ind;
-            if cur_index - orig_index == subpacket_length {
-                break;
+
+    fn read(&mut self, n: usize, expected: &str) -> Result<usize, ParseError> {
+        let bits = self.bits.get(self.pos..self.pos + n).ok_or_else(|| self.error_at(self.pos, expected))?;
+        self.pos += n;
+        Ok(bits.iter().fold(0, |res, &v| (res << 1) | v as usize))
+    }
+
+    fn packet(&mut self) -> Result<Packet, ParseError> {
+        let start = self.pos;
+        let version = self.read(3, "a 3 bit packet version")?;
+        let type_id = self.read(3, "a 3 bit packet type")?;
+        // Literal value
+        if type_id == 4 {
+            return Ok(Packet { version, contents: Contents::Literal(self.literal()?) });
+        }
+        let mut subpackets = vec![];
+        if self.read(1, "a length type")? == 0 {
+            let length = self.read(15, "a 15 bit subpacket length")?;
+            let end = self.pos + length;
+            while self.pos < end {
+                let sub = self.pos;
+                subpackets.push(self.packet()?);
+                if self.pos > end {
+                    return Err(self.error_at(sub, &format!("subpackets within {} bits", length)));
+                }
+            }
+        } else {
+            let count = self.read(11, "an 11 bit subpacket count")?;
+            for _ in 0..count {
+                subpackets.push(self.packet()?);
             }
         }
+        match (type_id, subpackets.len()) {
+            (0..=3, 0) => Err(self.error_at(start, "at least one subpacket")),
+            (5..=7, n) if n != 2 => Err(self.error_at(start, "two subpackets")),
+            _ => Ok(Packet { version, contents: Contents::Operator { type_id, subpackets } }),
+        }
     }
-    else
-    {
-        // Length type ID = 1
-        let num_subpackets = binary_to_decimal(&packet[cur_index..(cur_index + 11)]);
-        cur_index += 11;
-        for _ in 0..num_subpackets {
-            let (ind, ver, number) = process_packet(packet, cur_index);
-            subpackets.push(number);
-            version += ver;
-            cur_index = ind;
+
+    fn literal(&mut self) -> Result<usize, ParseError> {
+        let start = self.pos;
+        let mut val: usize = 0;
+        loop {
+            let group = self.read(5, "a 5 bit literal group")?;
+            val = val.checked_mul(16)
+                .map(|v| v | (group & 0xf))
+                .ok_or_else(|| self.error_at(start, "a literal of at most 64 bits"))?;
+            if group & 0x10 == 0 {
+                return Ok(val);
+            }
         }
     }
-    let result = match type_id {
-        0 => subpackets.into_iter().sum::<usize>(),
-        1 => subpackets.into_iter().product(),
-        2 => subpackets.into_iter().min().unwrap(),
-        3 => subpackets.into_iter().max().unwrap(),
-        5 => match subpackets[0] > subpackets[1] {
-            true => 1,
-            false => 0,
-        },
-        6 => match subpackets[0] < subpackets[1] {
-            true => 1,
-            false => 0,
-        },
-        7 => match subpackets[0] == subpackets[1] {
-            true => 1,
-            false => 0,
-        },
-        _ => panic!("Unexpected type_id {}", type_id),
-    };
-    (cur_index, version, result)
 }
 
-fn process_literal(packet: &Vec<u8>, start_index: usize) -> (usize, usize) {
-    let mut cur_index = start_index;
-    let mut num = vec![];
-    loop {
-        let block = &packet[cur_index..(cur_index + 5)];
-        num.extend(&block[1..]);
-        cur_index += 5;
-        if block[0] == 0 {
-            break;
+/// Decode the outermost packet of a hexadecimal transmission. Bits left
+/// over after it are padding.
+pub fn decode(line: &str) -> Result<Packet, ParseError> {
+    Reader { line, bits: get_binary(line)?, pos: 0 }.packet()
+}
+
+// Return version sum and result
+pub fn control_loop(packet: &Packet) -> (usize, usize) {
+    match &packet.contents {
+        Contents::Literal(number) => (packet.version, *number),
+        Contents::Operator { type_id, subpackets } => {
+            let (versions, values): (Vec<usize>, Vec<usize>) = subpackets.iter().map(control_loop).unzip();
+            let result = match type_id {
+                0 => values.into_iter().sum::<usize>(),
+                1 => values.into_iter().product(),
+                2 => values.into_iter().min().unwrap(),
+                3 => values.into_iter().max().unwrap(),
+                5 => (values[0] > values[1]) as usize,
+                6 => (values[0] < values[1]) as usize,
+                _ => (values[0] == values[1]) as usize,
+            };
+            (packet.version + versions.iter().sum::<usize>(), result)
         }
     }
-    let val = binary_to_decimal(&num);
-    (cur_index, val)
+}
+
+/// The outermost packet of the transmission
+pub struct Day16 {
+    packet: Packet,
+}
+
+impl Solution for Day16 {
+    const DAY: u32 = 16;
+
+    fn parse(input: &str) -> Result<Self, Error> {
+        // The transmission is a single line, anything after it is ignored
+        let line = input.lines().next().unwrap_or("");
+        Ok(Day16 { packet: decode(line.trim())? })
+    }
+
+    fn part1(&self) -> Answer {
+        control_loop(&self.packet).0.into()
+    }
+
+    fn part2(&self) -> Answer {
+        control_loop(&self.packet).1.into()
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use aoc_common::read_lines;
     #[test]
     fn example() {
-        let strings = read_input("example").unwrap();
+        let strings = read_lines("example").unwrap();
         let mut result = vec![];
-        for (i, input) in strings.iter().enumerate() {
-            let val = get_binary(input);
+        for input in strings.iter() {
+            let val = decode(input).unwrap();
             result.push(control_loop(&val));
         }
         for (i, val1) in strings.iter().enumerate() {
@@ -135,8 +162,30 @@ mod tests {
 
     #[test]
     fn actual() {
-        let input = get_binary(&read_input("input").unwrap()[0]);
+        let input = decode(&read_lines("input").unwrap()[0]).unwrap();
         let result = control_loop(&input);
         println!("Part1: {} Part2: {}", result.0, result.1);
     }
+
+    #[test]
+    fn bad_input() {
+        assert_eq!(get_binary("D2FE28"), Ok(vec![1, 1, 0, 1, 0, 0, 1, 0, 1, 1, 1, 1, 1, 1, 1, 0, 0, 0, 1, 0, 1, 0, 0, 0]));
+        assert_eq!(get_binary("D2FG28"), Err(ParseError::new(1, 4, "G", "a hexadecimal digit")));
+        assert_eq!(get_binary(""), Err(ParseError::new(1, 1, "", "a hexadecimal transmission")));
+    }
+
+    #[test]
+    fn truncated() {
+        // Cut off inside the third nested operator
+        assert_eq!(decode("8A004A80"), Err(ParseError::new(1, 7, "8", "an 11 bit subpacket count")));
+        assert_eq!(decode("D2FE"), Err(ParseError::new(1, 5, "", "a 5 bit literal group")));
+        assert_eq!(decode("38"), Err(ParseError::new(1, 2, "8", "a 15 bit subpacket length")));
+        // Two literals of 11 bits each declared to fit in 20 bits
+        assert_eq!(decode("38005045291200"), Err(ParseError::new(1, 9, "2", "subpackets within 20 bits")));
+        // Less-than with a single subpacket
+        assert_eq!(decode("DA004408"), Err(ParseError::new(1, 1, "D", "two subpackets")));
+        // Sum with no subpackets
+        assert_eq!(decode("00000000"), Err(ParseError::new(1, 1, "0", "at least one subpacket")));
+        assert_eq!(decode("D2FE28").map(|p| control_loop(&p)), Ok((6, 2021)));
+    }
 }
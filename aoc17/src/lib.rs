@@ -1,10 +1,7 @@
 use std::cmp::max;
+use aoc_common::{column, parse_field, split_field, Answer, Error, ParseError, Solution};
 
-fn nsum(n: i32) -> i32 {
-    n * (n + 1) / 2
-}
-
-fn simulate(vxi: i32, vyi: i32, xmin: i32, xmax: i32, ymin: i32, ymax: i32) -> Option<i32> {
+pub fn simulate(vxi: i32, vyi: i32, xmin: i32, xmax: i32, ymin: i32, ymax: i32) -> Option<i32> {
     let mut x = 0;
     let mut y = 0;
     let mut maxy = 0;
@@ -36,27 +33,80 @@ fn simulate(vxi: i32, vyi: i32, xmin: i32, xmax: i32, ymin: i32, ymax: i32) -> O
     }
 }
 
+/// Parse `target area: x=20..30, y=-10..-5` into (xmin, xmax, ymin, ymax)
+pub fn parse_input(input: &str) -> Result<(i32, i32, i32, i32), ParseError> {
+    let line = input.lines().next().unwrap_or("");
+    let expected = "target area: x=A..B, y=C..D";
+    let area = line.strip_prefix("target area: ").ok_or_else(|| ParseError::new(1, 1, line, expected))?;
+    let (x, y) = split_field(1, line, area, ", ", expected)?;
+    let range = |axis: &str, name: &str| -> Result<(i32, i32), ParseError> {
+        let bounds = axis.strip_prefix(name).ok_or_else(|| ParseError::new(1, column(line, axis), axis, name))?;
+        let (start, end) = split_field(1, line, bounds, "..", "a range like A..B")?;
+        Ok((parse_field(1, line, start, "a number")?, parse_field(1, line, end, "a number")?))
+    };
+    let (xmin, xmax) = range(x, "x=")?;
+    let (ymin, ymax) = range(y, "y=")?;
+    Ok((xmin, xmax, ymin, ymax))
+}
+
+/// Try every launch velocity that can possibly reach the target (which lies below
+/// and to the right of the probe). Returns the highest point reached and the number
+/// of velocities that hit the target.
+pub fn search(xmin: i32, xmax: i32, ymin: i32, ymax: i32) -> (i32, usize) {
+    let mut maxval = 0;
+    let mut count = 0;
+    for vx in 1..=xmax {
+        for vy in ymin..=-ymin {
+            if let Some(v) = simulate(vx, vy, xmin, xmax, ymin, ymax) {
+                count += 1;
+                maxval = max(maxval, v);
+            }
+        }
+    }
+    (maxval, count)
+}
+
+/// Target area as (xmin, xmax, ymin, ymax)
+pub struct Day17 {
+    target: (i32, i32, i32, i32),
+}
+
+impl Solution for Day17 {
+    const DAY: u32 = 17;
+
+    fn parse(input: &str) -> Result<Self, Error> {
+        Ok(Day17 { target: parse_input(input)? })
+    }
+
+    fn part1(&self) -> Answer {
+        let (xmin, xmax, ymin, ymax) = self.target;
+        search(xmin, xmax, ymin, ymax).0.into()
+    }
+
+    fn part2(&self) -> Answer {
+        let (xmin, xmax, ymin, ymax) = self.target;
+        search(xmin, xmax, ymin, ymax).1.into()
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use aoc_common::read_input;
     #[test]
     fn example() {
         let mut maxval = 0;
         let mut maxx = -1;
         let mut maxy = -1;
-        let (xmin, xmax, ymin, ymax) = (20, 30, -10, -5);
+        let (xmin, xmax, ymin, ymax) = parse_input(&read_input("example").unwrap()).unwrap();
         for vx in 1..10 {
             for vy in 1..10 {
-                match simulate(vx, vy, xmin, xmax, ymin, ymax) {
-                    Some(v) => {
-                        if (v > maxval)
-                        {
-                            maxval = v;
-                            maxx = vx;
-                            maxy = vy;
-                        }
-                    },
-                    None => (),
+                if let Some(v) = simulate(vx, vy, xmin, xmax, ymin, ymax) {
+                    if v > maxval {
+                        maxval = v;
+                        maxx = vx;
+                        maxy = vy;
+                    }
                 }
             }
         }
@@ -69,20 +119,16 @@ mod tests {
         let mut maxx = -1;
         let mut maxy = -1;
         let mut count = 0;
-        let (xmin, xmax, ymin, ymax) = (70, 125, -159, -121);
-        for vx in (1..200) {
+        let (xmin, xmax, ymin, ymax) = parse_input(&read_input("input").unwrap()).unwrap();
+        for vx in 1..200 {
             for vy in -200..200 {
-                match simulate(vx, vy, xmin, xmax, ymin, ymax) {
-                    Some(v) => {
-                        count += 1;
-                        if (v > maxval)
-                        {
-                            maxval = v;
-                            maxx = vx;
-                            maxy = vy;
-                        }
-                    },
-                    None => (),
+                if let Some(v) = simulate(vx, vy, xmin, xmax, ymin, ymax) {
+                    count += 1;
+                    if v > maxval {
+                        maxval = v;
+                        maxx = vx;
+                        maxy = vy;
+                    }
                 }
             }
         }
@@ -1,60 +1,58 @@
-use std::io::{BufReader, Error, ErrorKind};
-use std::io::prelude::*;
-use std::fs::File;
 use std::cmp::max;
+use aoc_common::{lines, Answer, Error, ParseError, Solution};
 
+/// One token of a flattened snailfish number
 #[derive(Debug, Copy, Clone, PartialEq, Eq)]
-enum Pl {
+pub enum Pl {
     Num(u32),
     Left,
     Right
 }
 
-#[derive(Debug, PartialEq, Eq)]
-enum Op {
-    Explode,
-    Split
-}
-
-fn read_input(filename: &str) -> Result<Vec<String>, Error> {
-    let f = File::open(filename).unwrap();
-    let f = BufReader::new(f);
-    f.lines().map(|l| l.and_then(|v| v.parse().map_err(|e| Error::new(ErrorKind::InvalidData, e)))).collect()
-}
-
-fn parse_input(input: Vec<String>) -> Vec<Vec<Pl>> {
-    input.iter().map(|val| {
+pub fn parse_input(input: Vec<String>) -> Result<Vec<Vec<Pl>>, ParseError> {
+    input.iter().enumerate().map(|(i, val)| {
         let mut res = vec![];
-        for c in val.trim().chars() {
-            if (c == ',') {
+        let mut depth = 0;
+        for (j, c) in val.char_indices() {
+            let bad = |expected: &str| ParseError::new(i + 1, j + 1, &val[j..j + c.len_utf8()], expected);
+            if c == ',' {
                 continue;
             }
             else if c == '[' {
+                depth += 1;
                 res.push(Pl::Left);
             }
             else if c == ']' {
+                if depth == 0 {
+                    return Err(bad("a number or ["));
+                }
+                depth -= 1;
                 res.push(Pl::Right);
             }
             else {
-                res.push(Pl::Num(c.to_digit(10).unwrap()));
+                res.push(Pl::Num(c.to_digit(10).ok_or_else(|| bad("a digit, [, ] or ,"))?));
             }
         }
-    res
+        if res.is_empty() {
+            return Err(ParseError::new(i + 1, 1, "", "a snailfish number"));
+        }
+        if depth != 0 {
+            return Err(ParseError::new(i + 1, val.len() + 1, "", "a closing ]"));
+        }
+        Ok(res)
     }).collect()
 }
 
-fn add(v1: &Vec<Pl>, v2: &Vec<Pl>) -> Vec<Pl> {
+pub fn add(v1: &[Pl], v2: &[Pl]) -> Vec<Pl> {
     let mut res = vec![Pl::Left];
-    let mut v1 = v1.clone();
-    let mut v2 = v2.clone();
-    res.append(&mut v1);
-    res.append(&mut v2);
+    res.extend_from_slice(v1);
+    res.extend_from_slice(v2);
     res.push(Pl::Right);
     res
 }
 
 /// At any time, explode has priority over split, and only one operation is done in a turn. 
-fn reduce(pair: Vec<Pl>) -> Vec<Pl> {
+pub fn reduce(pair: Vec<Pl>) -> Vec<Pl> {
     //let mut stack = vec![];
     let mut value = pair;
     let mut brace_count = 0;
@@ -125,17 +123,14 @@ fn reduce(pair: Vec<Pl>) -> Vec<Pl> {
                             // Store for later regular number update
                             next_right_val = Some(right_val);
                             // Update last_left regular value if it exists
-                            match last_left_ind {
-                                Some(ind) => {
-                                    if let Pl::Num(vv) = value[ind] {
-                                        new_val[ind] = Pl::Num(vv + left_val);
-                                        //println!("Explode op1 index {} num {}", ind, vv + left_val);
-                                        if vv + left_val >= 10 {
-                                            split_count += 1;
-                                        }
+                            if let Some(ind) = last_left_ind {
+                                if let Pl::Num(vv) = value[ind] {
+                                    new_val[ind] = Pl::Num(vv + left_val);
+                                    //println!("Explode op1 index {} num {}", ind, vv + left_val);
+                                    if vv + left_val >= 10 {
+                                        split_count += 1;
                                     }
-                                },
-                                None => (),
+                                }
                             }
                             brace_count -= 1;
                             new_val.push(Pl::Num(0)); // Add zero and skip the coming pair
@@ -205,7 +200,7 @@ fn reduce(pair: Vec<Pl>) -> Vec<Pl> {
     value
 }
 
-fn magnitude(input: &Vec<Pl>) -> u32 {
+pub fn magnitude(input: &[Pl]) -> u32 {
     let mut stack = vec![];
     for val in input {
         match val {
@@ -221,15 +216,59 @@ fn magnitude(input: &Vec<Pl>) -> u32 {
     stack.pop().unwrap()
 }
 
+/// Magnitude of the sum of every number in order
+pub fn final_sum(input: &[Vec<Pl>]) -> u32 {
+    let mut val = input[0].clone();
+    for val2 in input.iter().skip(1) {
+        val = reduce(add(&val, val2));
+    }
+    magnitude(&val)
+}
+
+/// Largest magnitude from adding any two different numbers
+pub fn largest_pair(input: &[Vec<Pl>]) -> u32 {
+    let mut maxval = 0;
+    for i in 0..input.len() {
+        for j in 0..input.len() {
+            if i == j {
+                continue;
+            }
+            maxval = max(maxval, magnitude(&reduce(add(&input[i], &input[j]))));
+        }
+    }
+    maxval
+}
+
+/// Snailfish numbers of the homework assignment
+pub struct Day18 {
+    numbers: Vec<Vec<Pl>>,
+}
+
+impl Solution for Day18 {
+    const DAY: u32 = 18;
+
+    fn parse(input: &str) -> Result<Self, Error> {
+        Ok(Day18 { numbers: parse_input(lines(input))? })
+    }
+
+    fn part1(&self) -> Answer {
+        final_sum(&self.numbers).into()
+    }
+
+    fn part2(&self) -> Answer {
+        largest_pair(&self.numbers).into()
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use aoc_common::read_lines;
     #[test]
     fn example2() {
-        let mut input = parse_input(read_input("example2").unwrap());
+        let mut input = parse_input(read_lines("example2").unwrap()).unwrap();
         let mut val = input.remove(0);
-        for i in 0..input.len() {
-            let val2 = input.remove(0);
+        for val2 in input {
             val = reduce(add(&val, &val2));
             //println!("{:?}", val);
             //println!("");
@@ -239,7 +278,7 @@ mod tests {
 
     #[test]
     fn examples_part2() {
-        let mut input = parse_input(read_input("example").unwrap());
+        let input = parse_input(read_lines("example").unwrap()).unwrap();
         let mut maxval = 0;
         for i in 0..input.len() {
             for j in 0..input.len() {
@@ -253,11 +292,11 @@ mod tests {
         println!("Part2 example {}", maxval);
     }
 
+    #[test]
     fn example() {
-        let mut input = parse_input(read_input("example").unwrap());
+        let mut input = parse_input(read_lines("example").unwrap()).unwrap();
         let mut val = input.remove(0);
-        for i in 0..input.len() {
-            let val2 = input.remove(0);
+        for val2 in input {
             val = reduce(add(&val, &val2));
             //println!("{:?}", val);
             //println!("");
@@ -267,17 +306,16 @@ mod tests {
 
     #[test]
     fn test_reduce() {
-        let mut input = parse_input(read_input("reduce1").unwrap());
+        let _input = parse_input(read_lines("reduce1").unwrap()).unwrap();
         //println!("{:?}", reduce(input.remove(0)));
         //println!("test output {:?}", reduce(input.remove(1)));
     }
 
     #[test]
     fn actual() {
-        let mut input = parse_input(read_input("input").unwrap());
+        let mut input = parse_input(read_lines("input").unwrap()).unwrap();
         let mut val = input.remove(0);
-        for i in 0..input.len() {
-            let val2 = input.remove(0);
+        for val2 in input {
             val = reduce(add(&val, &val2));
             //println!("{:?}", val);
             //println!("");
@@ -287,7 +325,7 @@ mod tests {
 
     #[test]
     fn actual_part2() {
-        let mut input = parse_input(read_input("input").unwrap());
+        let input = parse_input(read_lines("input").unwrap()).unwrap();
         let mut maxval = 0;
         for i in 0..input.len() {
             for j in 0..input.len() {
@@ -300,4 +338,13 @@ mod tests {
         }
         println!("Part2 actual: {}", maxval);
     }
+
+    #[test]
+    fn bad_input() {
+        let parse = |line: &str| parse_input(vec![line.to_owned()]);
+        assert_eq!(parse("[[1,2],3]"), Ok(vec![vec![Pl::Left, Pl::Left, Pl::Num(1), Pl::Num(2), Pl::Right, Pl::Num(3), Pl::Right]]));
+        assert_eq!(parse("[[1,2],x]"), Err(ParseError::new(1, 8, "x", "a digit, [, ] or ,")));
+        assert_eq!(parse("[[1,2],3"), Err(ParseError::new(1, 9, "", "a closing ]")));
+        assert_eq!(parse("[1,2]]"), Err(ParseError::new(1, 6, "]", "a number or [")));
+    }
 }
@@ -0,0 +1,262 @@
+use std::collections::{HashMap, HashSet, VecDeque};
+use aoc_common::{column, lines, parse_field, Answer, Error, ParseError, Solution};
+
+/// Position of a beacon or scanner as [x, y, z]
+pub type Point = [i32; 3];
+
+/// Two scanners overlap when they share at least this many beacons
+const OVERLAP: usize = 12;
+
+/// Beacons seen by each scanner, relative to that scanner
+pub fn parse_input(input: &[String]) -> Result<Vec<Vec<Point>>, ParseError> {
+    let mut scanners: Vec<Vec<Point>> = vec![];
+    for (i, val) in input.iter().enumerate() {
+        let s = val.trim();
+        if s.is_empty() {
+            continue;
+        }
+        if s.starts_with("---") {
+            let id = s.strip_prefix("--- scanner ")
+                .and_then(|v| v.strip_suffix(" ---"))
+                .ok_or_else(|| ParseError::new(i + 1, column(val, s), s, "--- scanner N ---"))?;
+            if parse_field::<usize>(i + 1, val, id, "a scanner number")? != scanners.len() {
+                return Err(ParseError::new(i + 1, column(val, id), id, &format!("scanner {}", scanners.len())));
+            }
+            scanners.push(vec![]);
+            continue;
+        }
+        let scanner = scanners.last_mut()
+            .ok_or_else(|| ParseError::new(i + 1, column(val, s), s, "--- scanner 0 ---"))?;
+        let fields: Vec<&str> = s.split(',').collect();
+        if fields.len() != 3 {
+            return Err(ParseError::new(i + 1, column(val, s), s, "x,y,z"));
+        }
+        let mut point = [0; 3];
+        for (v, field) in point.iter_mut().zip(fields) {
+            *v = parse_field(i + 1, val, field, "a number")?;
+        }
+        scanner.push(point);
+    }
+    if scanners.is_empty() {
+        return Err(ParseError::new(1, 1, "", "--- scanner 0 ---"));
+    }
+    Ok(scanners)
+}
+
+/// The 24 ways a scanner can face, as an axis permutation and the sign of each axis
+fn rotations() -> Vec<([usize; 3], [i32; 3])> {
+    let mut res = vec![];
+    for (perm, parity) in [([0, 1, 2], 1), ([1, 2, 0], 1), ([2, 0, 1], 1), ([0, 2, 1], -1), ([2, 1, 0], -1), ([1, 0, 2], -1)] {
+        for flips in 0..8 {
+            let signs = [flips & 1, (flips >> 1) & 1, (flips >> 2) & 1].map(|bit| 1 - 2 * bit);
+            // Flipping an odd permutation needs an odd number of sign changes to stay a rotation
+            if signs.iter().product::<i32>() == parity {
+                res.push((perm, signs));
+            }
+        }
+    }
+    res
+}
+
+fn rotate(p: &Point, (perm, signs): &([usize; 3], [i32; 3])) -> Point {
+    [p[perm[0]] * signs[0], p[perm[1]] * signs[1], p[perm[2]] * signs[2]]
+}
+
+/// Squared distances between every pair of beacons, which do not depend on
+/// where a scanner is or which way it faces
+fn fingerprint(report: &[Point]) -> Vec<i64> {
+    let mut res = vec![];
+    for (k, a) in report.iter().enumerate() {
+        for b in report[k + 1..].iter() {
+            res.push((0..3).map(|i| ((a[i] - b[i]) as i64).pow(2)).sum());
+        }
+    }
+    res.sort_unstable();
+    res
+}
+
+/// Number of values in both sorted lists, counting repeats
+fn shared(a: &[i64], b: &[i64]) -> usize {
+    let (mut i, mut j, mut res) = (0, 0, 0);
+    while i < a.len() && j < b.len() {
+        match a[i].cmp(&b[j]) {
+            std::cmp::Ordering::Less => i += 1,
+            std::cmp::Ordering::Greater => j += 1,
+            std::cmp::Ordering::Equal => {
+                res += 1;
+                i += 1;
+                j += 1;
+            }
+        }
+    }
+    res
+}
+
+/// Find the rotation and translation that put at least 12 beacons of
+/// `report` on top of `known`. Returns the position of the scanner and its
+/// beacons in the frame of `known`.
+pub fn align(known: &[Point], report: &[Point]) -> Option<(Point, Vec<Point>)> {
+    for rotation in rotations().iter() {
+        let rotated: Vec<Point> = report.iter().map(|p| rotate(p, rotation)).collect();
+        let mut offsets: HashMap<Point, usize> = HashMap::new();
+        for a in known {
+            for b in rotated.iter() {
+                *offsets.entry([a[0] - b[0], a[1] - b[1], a[2] - b[2]]).or_insert(0) += 1;
+            }
+        }
+        if let Some((&offset, _)) = offsets.iter().find(|(_, &n)| n >= OVERLAP) {
+            let beacons = rotated.iter().map(|p| [p[0] + offset[0], p[1] + offset[1], p[2] + offset[2]]).collect();
+            return Some((offset, beacons));
+        }
+    }
+    None
+}
+
+/// Every beacon and scanner in the frame of scanner 0
+#[derive(Debug)]
+pub struct Map {
+    pub beacons: HashSet<Point>,
+    pub scanners: Vec<Point>,
+}
+
+/// Align every scanner, starting from scanner 0 and working outwards through
+/// overlapping pairs. Fails with the first scanner that does not overlap
+/// with the rest.
+pub fn locate(reports: &[Vec<Point>]) -> Result<Map, usize> {
+    let fingerprints: Vec<Vec<i64>> = reports.iter().map(|r| fingerprint(r)).collect();
+    // 12 shared beacons have at least 66 distances in common
+    let min_shared = OVERLAP * (OVERLAP - 1) / 2;
+    let mut positions: Vec<Option<Point>> = vec![None; reports.len()];
+    let mut absolute: Vec<Vec<Point>> = vec![vec![]; reports.len()];
+    positions[0] = Some([0, 0, 0]);
+    absolute[0] = reports[0].clone();
+
+    let mut q = VecDeque::new();
+    q.push_back(0);
+    while let Some(k) = q.pop_front() {
+        for j in 0..reports.len() {
+            if positions[j].is_some() || shared(&fingerprints[k], &fingerprints[j]) < min_shared {
+                continue;
+            }
+            if let Some((position, beacons)) = align(&absolute[k], &reports[j]) {
+                positions[j] = Some(position);
+                absolute[j] = beacons;
+                q.push_back(j);
+            }
+        }
+    }
+    if let Some(k) = positions.iter().position(|p| p.is_none()) {
+        return Err(k);
+    }
+    Ok(Map {
+        beacons: absolute.into_iter().flatten().collect(),
+        scanners: positions.into_iter().flatten().collect(),
+    })
+}
+
+pub fn largest_distance(scanners: &[Point]) -> i32 {
+    let mut res = 0;
+    for a in scanners {
+        for b in scanners {
+            res = res.max((0..3).map(|i| (a[i] - b[i]).abs()).sum());
+        }
+    }
+    res
+}
+
+/// Every beacon and scanner, aligned once while parsing
+pub struct Day19 {
+    map: Map,
+}
+
+impl Solution for Day19 {
+    const DAY: u32 = 19;
+
+    fn parse(input: &str) -> Result<Self, Error> {
+        let input = lines(input);
+        let map = locate(&parse_input(&input)?).map_err(|k| {
+            let header = format!("--- scanner {} ---", k);
+            let i = input.iter().position(|v| v.trim() == header).unwrap_or(0);
+            let s = input[i].trim();
+            ParseError::new(i + 1, column(&input[i], s), s, "a scanner overlapping another")
+        })?;
+        Ok(Day19 { map })
+    }
+
+    fn part1(&self) -> Answer {
+        self.map.beacons.len().into()
+    }
+
+    fn part2(&self) -> Answer {
+        largest_distance(&self.map.scanners).into()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use aoc_common::read_lines;
+    #[test]
+    fn example() {
+        let input = parse_input(&read_lines("example").unwrap()).unwrap();
+        let map = locate(&input).unwrap();
+        println!("Part1: {}", map.beacons.len());
+        println!("Part2: {}", largest_distance(&map.scanners));
+        assert_eq!(map.scanners, vec![[0, 0, 0], [68, -1246, -43], [1105, -1205, 1229], [-92, -2380, -20], [-20, -1133, 1061]]);
+    }
+
+    #[test]
+    fn generated() {
+        let input = parse_input(&read_lines("example-generated").unwrap()).unwrap();
+        let map = locate(&input).unwrap();
+        println!("Part1: {}", map.beacons.len());
+        println!("Part2: {}", largest_distance(&map.scanners));
+    }
+
+    #[test]
+    fn actual() {
+        let input = parse_input(&read_lines("input").unwrap()).unwrap();
+        let map = locate(&input).unwrap();
+        println!("Part1: {}", map.beacons.len());
+        println!("Part2: {}", largest_distance(&map.scanners));
+    }
+
+    #[test]
+    fn orientations() {
+        let rotations = rotations();
+        let turned: HashSet<Point> = rotations.iter().map(|r| rotate(&[1, 2, 3], r)).collect();
+        assert_eq!(turned.len(), 24);
+        let input = parse_input(&read_lines("example").unwrap()).unwrap();
+        for r in rotations.iter() {
+            let report: Vec<Point> = input[0].iter().map(|p| rotate(p, r)).map(|p| [p[0] - 5, p[1], p[2] + 7]).collect();
+            let (position, beacons) = align(&input[0], &report).unwrap();
+            assert_eq!(beacons.into_iter().collect::<HashSet<Point>>(), input[0].iter().copied().collect());
+            assert_eq!(rotate(&position, r), [5, 0, -7]);
+        }
+    }
+
+    #[test]
+    fn bad_input() {
+        let input = |text: &str| text.lines().map(|s| s.to_owned()).collect::<Vec<String>>();
+        assert_eq!(parse_input(&input("1,2,3")), Err(ParseError::new(1, 1, "1,2,3", "--- scanner 0 ---")));
+        assert_eq!(parse_input(&input("--- scanner 1 ---")), Err(ParseError::new(1, 13, "1", "scanner 0")));
+        assert_eq!(parse_input(&input("--- scanner 0 ---\n1,2")), Err(ParseError::new(2, 1, "1,2", "x,y,z")));
+        assert_eq!(parse_input(&input("--- scanner 0 ---\n1,x,3")), Err(ParseError::new(2, 3, "x", "a number")));
+    }
+
+    #[test]
+    fn unconnected() {
+        let mut input = read_lines("example").unwrap();
+        let third = input.iter().position(|v| v == "--- scanner 2 ---").unwrap();
+        input.truncate(third);
+        // A scanner whose beacons line up with nobody else's
+        input.push("--- scanner 2 ---".to_owned());
+        input.extend((0..12).map(|k| format!("{},{},{}", k * k, 7 * k, -3 * k * k * k)));
+        let reports = parse_input(&input).unwrap();
+        assert_eq!(locate(&reports).err(), Some(2));
+        match Day19::parse(&input.join("\n")) {
+            Err(Error::Parse(e)) => assert_eq!(e, ParseError::new(third + 1, 1, "--- scanner 2 ---", "a scanner overlapping another")),
+            _ => panic!("expected a parse error"),
+        }
+    }
+}
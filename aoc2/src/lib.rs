@@ -1,66 +1,188 @@
-use std::io::{BufReader, Error, ErrorKind};
-use std::io::prelude::*;
-use std::fs::File;
-
-fn read_input(filename: &str) -> Result<Vec<String>, Error> {
-    let f = File::open(filename).unwrap();
-    let f = BufReader::new(f);
-    f.lines().map(|l| l.and_then(|v| v.parse().map_err(|e| Error::new(ErrorKind::InvalidData, e)))).collect()
+use std::fmt;
+use std::str::FromStr;
+use aoc_common::{column, lines, parse_field, Answer, Error, ParseError, Solution};
+
+mod trajectory;
+pub use trajectory::{LimitError, Limits, Trajectory};
+
+/// One line of the planned course
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum Command {
+    Forward(i64),
+    Down(i64),
+    Up(i64),
+    /// Reverse along the current heading. Extended mode only.
+    Back(i64),
+    /// Rise straight to depth 0 and level out. Extended mode only.
+    Surface,
+    /// Move forward keeping the current depth, whatever the aim. Extended mode only.
+    Hold(i64),
 }
 
-fn parse_input(input: &Vec<String>) -> u64 {
-    let mut h = 0;
-    let mut d = 0;
-    for val in input.iter() {
-        let val = val.split(" ").collect::<Vec<&str>>();
-        let (dir, mag) = (val[0], val[1]);
-        let mag = mag.parse::<u64>().unwrap();
-        match dir {
-            "forward" =>  h += mag,
-            "up" => d -= mag,
-            "down" => d += mag,
-            _ => panic!("Don't know what {} is", dir),
-        };
-    };
-    h * d
+/// Which commands a course may use
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum Mode {
+    /// `forward`, `down` and `up`, as in the puzzle
+    Standard,
+    /// Also `back`, `surface` and `hold`
+    Extended,
 }
 
-fn parse_input_with_aim(input: &Vec<String>) -> u64 {
-    let mut h = 0;
-    let mut d = 0;
-    let mut aim = 0;
-    for val in input.iter() {
-        let val = val.split(" ").collect::<Vec<&str>>();
-        let (dir, mag) = (val[0], val[1]);
-        let mag = mag.parse::<u64>().unwrap();
-        match dir {
-            "forward" =>  {
-                h += mag;
-                d += aim * mag;
-            },
-            "up" => aim -= mag,
-            "down" => aim += mag,
-            _ => panic!("Don't know what {} is", dir),
+impl Command {
+    /// Parse a single line, reported as line 1
+    pub fn parse(line: &str, mode: Mode) -> Result<Self, ParseError> {
+        let (dir, mag) = line.split_once(' ').unwrap_or((line, &line[line.len()..]));
+        let command = match (dir, mode) {
+            ("forward", _) => Command::Forward,
+            ("down", _) => Command::Down,
+            ("up", _) => Command::Up,
+            ("back", Mode::Extended) => Command::Back,
+            ("hold", Mode::Extended) => Command::Hold,
+            ("surface", Mode::Extended) if mag.is_empty() => return Ok(Command::Surface),
+            ("surface", Mode::Extended) => return Err(ParseError::new(1, column(line, mag), mag, "nothing after surface")),
+            (_, Mode::Standard) => return Err(ParseError::new(1, 1, dir, "forward, down or up")),
+            (_, Mode::Extended) => return Err(ParseError::new(1, 1, dir, "forward, down, up, back, surface or hold")),
         };
-    };
-    h * d
+        Ok(command(parse_field::<u32>(1, line, mag, "a distance")? as i64))
+    }
+}
+
+/// Parses a single standard command, reported as line 1
+impl FromStr for Command {
+    type Err = ParseError;
+
+    fn from_str(line: &str) -> Result<Self, ParseError> {
+        Command::parse(line, Mode::Standard)
+    }
+}
+
+impl fmt::Display for Command {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Command::Forward(n) => write!(f, "forward {}", n),
+            Command::Down(n) => write!(f, "down {}", n),
+            Command::Up(n) => write!(f, "up {}", n),
+            Command::Back(n) => write!(f, "back {}", n),
+            Command::Surface => write!(f, "surface"),
+            Command::Hold(n) => write!(f, "hold {}", n),
+        }
+    }
+}
+
+pub fn parse_commands(input: &[String], mode: Mode) -> Result<Vec<Command>, ParseError> {
+    input.iter()
+        .enumerate()
+        .map(|(i, line)| Command::parse(line, mode).map_err(|e| ParseError { line: i + 1, ..e }))
+        .collect()
+}
+
+/// How `down` and `up` are interpreted
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum Model {
+    /// `down` and `up` change the depth directly
+    Plain,
+    /// `down` and `up` turn the submarine, and `forward` also dives along the aim
+    Aim,
+}
+
+/// Where the submarine is. Depth grows downwards; nothing stops it from
+/// rising above the surface, which gives a negative depth.
+#[derive(Debug, Copy, Clone, Default, PartialEq, Eq)]
+pub struct Submarine {
+    pub position: i64,
+    pub depth: i64,
+    pub aim: i64,
+}
+
+impl Submarine {
+    pub fn apply(&mut self, command: Command, model: Model) {
+        match (command, model) {
+            (Command::Forward(n), Model::Plain) => self.position += n,
+            (Command::Forward(n), Model::Aim) => {
+                self.position += n;
+                self.depth += self.aim * n;
+            }
+            (Command::Down(n), Model::Plain) => self.depth += n,
+            (Command::Up(n), Model::Plain) => self.depth -= n,
+            (Command::Down(n), Model::Aim) => self.aim += n,
+            (Command::Up(n), Model::Aim) => self.aim -= n,
+            (Command::Back(n), Model::Plain) => self.position -= n,
+            (Command::Back(n), Model::Aim) => {
+                self.position -= n;
+                self.depth -= self.aim * n;
+            }
+            (Command::Surface, _) => {
+                self.depth = 0;
+                self.aim = 0;
+            }
+            (Command::Hold(n), _) => self.position += n,
+        }
+    }
+
+    /// Follow every command from the starting point
+    pub fn run(commands: &[Command], model: Model) -> Self {
+        let mut sub = Submarine::default();
+        for &command in commands {
+            sub.apply(command, model);
+        }
+        sub
+    }
+
+    /// Horizontal position multiplied by depth
+    pub fn product(&self) -> i64 {
+        self.position * self.depth
+    }
+}
+
+/// Planned course, one command per line
+pub struct Day2 {
+    commands: Vec<Command>,
+}
+
+impl Solution for Day2 {
+    const DAY: u32 = 2;
+
+    fn parse(input: &str) -> Result<Self, Error> {
+        Ok(Day2 { commands: parse_commands(&lines(input), Mode::Standard)? })
+    }
+
+    fn part1(&self) -> Answer {
+        Submarine::run(&self.commands, Model::Plain).product().into()
+    }
+
+    fn part2(&self) -> Answer {
+        Submarine::run(&self.commands, Model::Aim).product().into()
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use aoc_common::read_lines;
     #[test]
     fn example()
     {
-        let input = read_input("example").unwrap();
-        println!("Part1: {}", parse_input(&input));
-        println!("Part2: {}", parse_input_with_aim(&input))
+        let input = parse_commands(&read_lines("example").unwrap(), Mode::Standard).unwrap();
+        println!("Part1: {}", Submarine::run(&input, Model::Plain).product());
+        println!("Part2: {}", Submarine::run(&input, Model::Aim).product())
     }
     #[test]
     fn part1()
     {
-        let input = read_input("input").unwrap();
-        println!("Part1: {}", parse_input(&input));
-        println!("Part2: {}", parse_input_with_aim(&input))
+        let input = parse_commands(&read_lines("input").unwrap(), Mode::Standard).unwrap();
+        println!("Part1: {}", Submarine::run(&input, Model::Plain).product());
+        println!("Part2: {}", Submarine::run(&input, Model::Aim).product())
+    }
+
+    #[test]
+    fn commands() {
+        assert_eq!("forward 5".parse(), Ok(Command::Forward(5)));
+        assert_eq!("up 3".parse::<Command>().unwrap().to_string(), "up 3");
+        assert_eq!("sideways 2".parse::<Command>(), Err(ParseError::new(1, 1, "sideways", "forward, down or up")));
+        let input = vec!["down 1".to_owned(), "up -2".to_owned()];
+        assert_eq!(parse_commands(&input, Mode::Standard), Err(ParseError::new(2, 4, "-2", "a distance")));
+        // Rising before diving goes above the surface instead of underflowing
+        let sub = Submarine::run(&[Command::Up(4), Command::Forward(2)], Model::Plain);
+        assert_eq!(sub, Submarine { position: 2, depth: -4, aim: 0 });
     }
 }
@@ -0,0 +1,116 @@
+use std::fmt;
+
+use crate::{Command, Model, Submarine};
+
+/// Bounds the submarine must stay within, checked after every command
+#[derive(Debug, Copy, Clone, Default, PartialEq, Eq)]
+pub struct Limits {
+    pub max_depth: Option<i64>,
+    /// Use 0 to keep the submarine from rising above the surface
+    pub min_depth: Option<i64>,
+}
+
+/// A command took the submarine outside its limits. `step` is the 1-based
+/// index of the command and `state` where it would have ended up.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum LimitError {
+    TooDeep { step: usize, state: Submarine, max_depth: i64 },
+    TooShallow { step: usize, state: Submarine, min_depth: i64 },
+}
+
+impl fmt::Display for LimitError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            LimitError::TooDeep { step, state, max_depth } => {
+                write!(f, "command {}: depth {} is below the maximum depth {}", step, state.depth, max_depth)
+            }
+            LimitError::TooShallow { step, state, min_depth } => {
+                write!(f, "command {}: depth {} is above the minimum depth {}", step, state.depth, min_depth)
+            }
+        }
+    }
+}
+
+impl std::error::Error for LimitError {}
+
+/// Every command of a course with the state of the submarine after it
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Trajectory {
+    pub steps: Vec<(Command, Submarine)>,
+}
+
+impl Trajectory {
+    /// Follow `commands` from the starting point, stopping at the first one
+    /// that breaks `limits`
+    pub fn record(commands: &[Command], model: Model, limits: &Limits) -> Result<Self, LimitError> {
+        let mut sub = Submarine::default();
+        let mut steps = Vec::with_capacity(commands.len());
+        for (i, &command) in commands.iter().enumerate() {
+            sub.apply(command, model);
+            match (limits.max_depth, limits.min_depth) {
+                (Some(max_depth), _) if sub.depth > max_depth => {
+                    return Err(LimitError::TooDeep { step: i + 1, state: sub, max_depth });
+                }
+                (_, Some(min_depth)) if sub.depth < min_depth => {
+                    return Err(LimitError::TooShallow { step: i + 1, state: sub, min_depth });
+                }
+                _ => (),
+            }
+            steps.push((command, sub));
+        }
+        Ok(Trajectory { steps })
+    }
+
+    /// Where the submarine ends up, or the starting point for an empty course
+    pub fn last(&self) -> Submarine {
+        self.steps.last().map(|&(_, sub)| sub).unwrap_or_default()
+    }
+
+    /// One row per command, after a header row
+    pub fn to_csv(&self) -> String {
+        let mut csv = String::from("step,command,position,depth,aim\n");
+        for (i, (command, sub)) in self.steps.iter().enumerate() {
+            csv.push_str(&format!("{},{},{},{},{}\n", i + 1, command, sub.position, sub.depth, sub.aim));
+        }
+        csv
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{parse_commands, Mode};
+    use aoc_common::{read_lines, ParseError};
+    #[test]
+    fn example() {
+        let commands = parse_commands(&read_lines("example").unwrap(), Mode::Standard).unwrap();
+        let trajectory = Trajectory::record(&commands, Model::Aim, &Limits::default()).unwrap();
+        assert_eq!(trajectory.last(), Submarine::run(&commands, Model::Aim));
+        let csv = trajectory.to_csv();
+        assert_eq!(csv.lines().count(), commands.len() + 1);
+        assert!(csv.starts_with("step,command,position,depth,aim\n1,forward 5,5,0,0\n2,down 5,5,0,5\n"));
+
+        let limits = Limits { max_depth: Some(50), min_depth: Some(0) };
+        let err = Trajectory::record(&commands, Model::Aim, &limits).unwrap_err();
+        assert_eq!(err, LimitError::TooDeep { step: 6, state: Submarine { position: 15, depth: 60, aim: 10 }, max_depth: 50 });
+        assert_eq!(err.to_string(), "command 6: depth 60 is below the maximum depth 50");
+    }
+
+    #[test]
+    fn extended_commands() {
+        let input: Vec<String> = ["down 5", "forward 2", "back 1", "hold 4", "surface", "up 1"].iter().map(|s| s.to_string()).collect();
+        assert_eq!(parse_commands(&input, Mode::Standard), Err(ParseError::new(3, 1, "back", "forward, down or up")));
+        let commands = parse_commands(&input, Mode::Extended).unwrap();
+        let depths: Vec<i64> = Trajectory::record(&commands, Model::Aim, &Limits::default())
+            .unwrap()
+            .steps
+            .iter()
+            .map(|(_, sub)| sub.depth)
+            .collect();
+        assert_eq!(depths, vec![0, 10, 5, 5, 0, 0]);
+        let limits = Limits { max_depth: None, min_depth: Some(0) };
+        assert!(matches!(Trajectory::record(&commands, Model::Plain, &limits), Err(LimitError::TooShallow { step: 6, .. })));
+        assert!("surface now".parse::<Command>().is_err());
+        assert_eq!(Command::parse("surface now", Mode::Extended), Err(ParseError::new(1, 9, "now", "nothing after surface")));
+    }
+}
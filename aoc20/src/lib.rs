@@ -1,107 +1,86 @@
-use std::io::{BufReader, Error, ErrorKind};
-use std::io::prelude::*;
-use std::fs::File;
-use std::collections::HashMap;
-use std::cmp::{min, max};
+use aoc_common::{lines, Answer, Cell, Error, Grid, ParseError, Solution};
 
-fn read_input(filename: &str) -> Result<Vec<String>, Error> {
-    let f = File::open(filename).unwrap();
-    let f = BufReader::new(f);
-    f.lines().map(|l| l.and_then(|v| v.parse().map_err(|e| Error::new(ErrorKind::InvalidData, e)))).collect()
+pub fn parse_input(input: Vec<String>) -> Result<(Vec<bool>, Grid<bool>), ParseError> {
+    let first = input.first().map_or("", |v| v.as_str());
+    let algo = first.char_indices()
+        .map(|(j, c)| bool::from_char(c).ok_or_else(|| ParseError::new(1, j + 1, &first[j..j + c.len_utf8()], bool::EXPECTED)))
+        .collect::<Result<Vec<bool>, ParseError>>()?;
+    if algo.len() != 512 {
+        return Err(ParseError::new(1, 1, first, "a 512 pixel enhancement algorithm"));
+    }
+    if input.len() < 3 {
+        return Err(ParseError::new(input.len() + 1, 1, "", "an input image"));
+    }
+    // The image starts after the algorithm and a blank line
+    let image = Grid::from_lines(&input[2..]).map_err(|e| ParseError { line: e.line + 2, ..e })?;
+    Ok((algo, image))
 }
 
-fn parse_input(input: Vec<String>) -> (Vec<u8>, HashMap<(i32, i32), u8>) {
-    let algo: Vec<u8> = input[0].chars().map(|v| match v {
-        '.' => 0,
-        '#' => 1,
-        _ => panic!("Only . or #"),
-    }).collect();
-    let mut map = HashMap::new();
-    let mut tot: i32 = (input.len() - 3) as i32;
+pub fn enhance_x(image: &Grid<bool>, algo: &[bool], num_iter: usize) -> usize {
+    let mut image = image.clone();
+    // Everything outside the image is the same colour, which flips every
+    // iteration when the algorithm maps an all-dark square to lit
+    let mut background = false;
+    for _ in 0..num_iter {
+        let (new_image, new_background) = enhance_once(&image, algo, background);
+        image = new_image;
+        background = new_background;
+    }
+    image.iter().filter(|&&v| v).count()
+}
 
-    // Index in such a way that in the initial input, left bottom is (0, 0) and tbe input covers the first quadrant
-    // Keep only pixels that are lit to save space
-    for i in 2..input.len() {
-        let mut j: i32 = 0;
-        for c in input[i].chars() {
-            let val = match c {
-                '.' => 0,
-                '#' => 1,
-                _ => panic!("Only . or #"),
-            };
-            map.insert((j, tot), val);
-            j += 1;
+/// Grow the image by one pixel on every side and enhance it. Returns the
+/// new image and the new colour of the infinite background.
+fn enhance_once(image: &Grid<bool>, algo: &[bool], background: bool) -> (Grid<bool>, bool) {
+    let new_image = Grid::from_fn(image.width() + 2, image.height() + 2, |(i, j)| {
+        // Pixel (i, j) of the new image is pixel (i - 1, j - 1) of the old one,
+        // so its 3x3 square covers old rows i - 2..=i and columns j - 2..=j
+        let mut index_in_algo: usize = 0;
+        for x in i..i + 3 {
+            for y in j..j + 3 {
+                let lit = match x >= 2 && y >= 2 {
+                    true => image.get((x - 2, y - 2)).copied().unwrap_or(background),
+                    false => background,
+                };
+                index_in_algo = (index_in_algo << 1) | lit as usize;
+            }
         }
-        tot -= 1;
-    }
-    (algo, map)
+        algo[index_in_algo]
+    });
+    let new_background = algo[if background { 511 } else { 0 }];
+    (new_image, new_background)
 }
 
-fn enhance_x(image: &HashMap<(i32, i32), u8>, algo: &Vec<u8>, num_iter: usize) -> usize {
-    let mut map = image.clone();
-    //println!("Initial len: {}", map.len());
-    // Get min_x, max_x and min_y, max_y where you have all zeros.ans - 2 would be the boundary.
-    // For our input, the boundary would alternate between 0 and 1 every iteration
+/// Image enhancement algorithm and the input image
+pub struct Day20 {
+    algo: Vec<bool>,
+    image: Grid<bool>,
+}
 
-    let mut min_x: i32 = 10;
-    let mut max_x: i32 = 0;
-    let mut min_y: i32 = 10;
-    let mut max_y: i32 = 0;
-    for (kx, ky) in image.keys() {
-        min_x = min(min_x, *kx);
-        max_x = max(max_x, *kx);
-        min_y = min(min_y, *ky);
-        max_y = max(max_y, *ky);
-    }
-    //println!("Boundary init: {:?}", (min_x, max_x, min_y, max_y));
-    for i in 0..num_iter {
-        let mut ia = i as i32;
-        map = enhance_once(&map, &algo, (min_x - 1 - ia, max_x + 1 + ia, min_y - 1 - ia, max_y + 1 + ia), i);
+impl Solution for Day20 {
+    const DAY: u32 = 20;
+
+    fn parse(input: &str) -> Result<Self, Error> {
+        let (algo, image) = parse_input(lines(input))?;
+        Ok(Day20 { algo, image })
     }
-    map.values().filter(|v| **v == 1).count()
-}
 
-fn enhance_once(image: &HashMap<(i32, i32), u8>, algo: &Vec<u8>, boundary: (i32, i32, i32, i32), iter: usize) -> HashMap<(i32, i32), u8> {
-    let mut new_image = HashMap::new();
-    let mut boundary_val = 0;
-    if algo[0] == 1 && iter % 2 != 0 {
-        boundary_val = 1;
+    fn part1(&self) -> Answer {
+        enhance_x(&self.image, &self.algo, 2).into()
     }
-    //println!("iter: {} boundary: {:?} boundary_val {}", iter, boundary, boundary_val);
-    let neighbors = |i, j| {
-        let mut slice = [0; 9];
-        let mut ind = 0;
-        for kj in [1, 0, -1].iter() {
-            for ki in [-1, 0, 1].iter() {
-                let val = match image.get(&(i + ki, j + kj)) {
-                    Some(v) => *v,
-                    None => boundary_val,
-                };
-                slice[ind] = val;
-                ind += 1;
-            }
-        }
-        slice
-    };
 
-    for x in boundary.0..(boundary.1 + 1) {
-        for y in (boundary.2)..(boundary.3 + 1) {
-            let mut index_in_algo: usize = 0;
-            for v in neighbors(x, y) {
-                index_in_algo = (index_in_algo << 1) | (v as usize);
-            }
-            new_image.insert((x, y), algo[index_in_algo]);
-        }
+    fn part2(&self) -> Answer {
+        enhance_x(&self.image, &self.algo, 50).into()
     }
-    new_image
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use aoc_common::read_lines;
     #[test]
     fn example() {
-        let (algo, mut image) = parse_input(read_input("example").unwrap());
+        let (algo, image) = parse_input(read_lines("example").unwrap()).unwrap();
         assert_eq!(algo.len(), 512);
         println!("Part1: {}", enhance_x(&image, &algo, 2));
         println!("Part2: {}", enhance_x(&image, &algo, 50));
@@ -109,9 +88,18 @@ mod tests {
 
     #[test]
     fn actual() {
-        let (algo, mut image) = parse_input(read_input("input").unwrap());
+        let (algo, image) = parse_input(read_lines("input").unwrap()).unwrap();
         assert_eq!(algo.len(), 512);
         println!("Part1: {}", enhance_x(&image, &algo, 2));
         println!("Part2: {}", enhance_x(&image, &algo, 50));
     }
+
+    #[test]
+    fn bad_input() {
+        let mut input = read_lines("example").unwrap();
+        input[3].replace_range(2..3, "o");
+        assert_eq!(parse_input(input.clone()), Err(ParseError::new(4, 3, "o", "'#' or '.'")));
+        input[0].pop();
+        assert_eq!(parse_input(input.clone()), Err(ParseError::new(1, 1, &input[0], "a 512 pixel enhancement algorithm")));
+    }
 }
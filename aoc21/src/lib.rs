@@ -1,7 +1,8 @@
-use std::collections::{VecDeque, HashMap};
 use std::cmp::max;
+use std::collections::HashMap;
+use aoc_common::{column, lines, parse_field, Answer, Error, ParseError, Solution};
 
-fn game(start1: u32, start2: u32) -> u32 {
+pub fn game(start1: u32, start2: u32) -> u32 {
     let mut player1 = true;
     let mut score1 = 0;
     let mut score2 = 0;
@@ -30,21 +31,21 @@ fn game(start1: u32, start2: u32) -> u32 {
         num_turns += 1;
     }
     if score1 < 1000 {
-        return score1 * num_turns * 3
+        score1 * num_turns * 3
     }
     else {
-        return score2 * num_turns * 3
+        score2 * num_turns * 3
     }
 }
 
 // Fiendishly hard Part2: Essentially, simulate all the win states for both players
-fn quantum_game(start1: u64, start2: u64) -> (u64, u64) {
+pub fn quantum_game(start1: u64, start2: u64) -> (u64, u64) {
     // Distribution of x + y + z where x belongs to [1,2,3]
     // Total 3^3 = 27 possible combinations. 7 distinct sums (3-9)
     let arr = [(3, 1), (4, 3), (5, 6), (6, 7), (7, 6), (8, 3), (9, 1)]; 
 
 
-    let FINAL_SCORE = 21;
+    const FINAL_SCORE: u64 = 21;
 
     // Player loop: Simulate all possible scores/positions from all previously seen (position, score) keys.
     // Value is the number of universes with that combination of (pos, score) in that iteration for this player.
@@ -117,18 +118,56 @@ fn quantum_game(start1: u64, start2: u64) -> (u64, u64) {
 }
 
 
+/// Parse the two `Player N starting position: P` lines. Positions are 1-based.
+pub fn parse_input(input: &[String]) -> Result<(u32, u32), ParseError> {
+    let start = |i: usize| -> Result<u32, ParseError> {
+        let line = input.get(i).ok_or_else(|| ParseError::new(i + 1, 1, "", "a starting position"))?;
+        let pos = line.rsplit(' ').next().unwrap();
+        match parse_field(i + 1, line, pos, "a position from 1 to 10")? {
+            p @ 1..=10 => Ok(p),
+            _ => Err(ParseError::new(i + 1, column(line, pos), pos, "a position from 1 to 10")),
+        }
+    };
+    Ok((start(0)?, start(1)?))
+}
+
+/// Starting positions of both players, 1-based
+pub struct Day21 {
+    start: (u32, u32),
+}
+
+impl Solution for Day21 {
+    const DAY: u32 = 21;
+
+    fn parse(input: &str) -> Result<Self, Error> {
+        Ok(Day21 { start: parse_input(&lines(input))? })
+    }
+
+    fn part1(&self) -> Answer {
+        game(self.start.0 - 1, self.start.1 - 1).into()
+    }
+
+    fn part2(&self) -> Answer {
+        let (wins1, wins2) = quantum_game(self.start.0 as u64, self.start.1 as u64);
+        max(wins1, wins2).into()
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use aoc_common::read_lines;
     #[test]
     fn example() {
-        println!("Part1: {}", game(3, 7));
-        println!("Part2: {:?}", quantum_game(4, 8));
+        let (start1, start2) = parse_input(&read_lines("example").unwrap()).unwrap();
+        println!("Part1: {}", game(start1 - 1, start2 - 1));
+        println!("Part2: {:?}", quantum_game(start1 as u64, start2 as u64));
     }
 
     #[test]
     fn actual() {
-        println!("Part1: {}", game(6, 8));
-        println!("Part2: {:?}", quantum_game(7, 9));
+        let (start1, start2) = parse_input(&read_lines("input").unwrap()).unwrap();
+        println!("Part1: {}", game(start1 - 1, start2 - 1));
+        println!("Part2: {:?}", quantum_game(start1 as u64, start2 as u64));
     }
 }
@@ -1,51 +1,45 @@
-use std::io::{BufReader, Error, ErrorKind};
-use std::io::prelude::*;
-use std::fs::File;
-use std::collections::HashMap;
 use std::cmp::{min, max};
+use aoc_common::{column, lines, parse_field, split_field, Answer, Error, ParseError, Solution};
 
-fn read_input(filename: &str) -> Result<Vec<String>, Error> {
-    let f = File::open(filename).unwrap();
-    let f = BufReader::new(f);
-    f.lines().map(|l| l.and_then(|v| v.parse().map_err(|e| Error::new(ErrorKind::InvalidData, e)))).collect()
-}
-
+/// An on/off reboot step over an inclusive cuboid
 #[derive(Debug, Copy, Clone)]
-struct Range {
+pub struct Range {
     xr: [i32; 2],
     yr: [i32; 2],
     zr: [i32; 2],
     action: usize,
 }
 
-fn parse_input(input: Vec<String>) -> Vec<Range> {
+pub fn parse_input(input: Vec<String>) -> Result<Vec<Range>, ParseError> {
     let mut res = vec![];
-    for val in input {
+    for (line, val) in input.iter().enumerate() {
+        let line = line + 1;
         let mut tmp = vec![[0; 2], [0; 2], [0; 2]];
-        let mut action = 0;
-        let ss: Vec<&str> = val.trim().split(" ").collect();
-        if ss[0] == "on" {
-            action = 1;
-        }
-        let ranges: Vec<&str> = ss[1].split(",").collect();
-        for (i, r) in ranges.iter().enumerate() {
-            let val: Vec<&str> = r.split("=").collect::<Vec<&str>>()[1].split("..").collect();
-            let num1 = val[0].parse::<i32>().unwrap();
-            let num2 = val[1].parse::<i32>().unwrap();
-            tmp[i][0] = num1;
-            tmp[i][1] = num2;
+        let (switch, cuboid) = split_field(line, val, val.trim(), " ", "on or off followed by a cuboid")?;
+        let action = match switch {
+            "on" => 1,
+            "off" => 0,
+            _ => return Err(ParseError::new(line, column(val, switch), switch, "on or off")),
+        };
+        let mut ranges = cuboid.split(',');
+        for (i, axis) in ["x=", "y=", "z="].iter().enumerate() {
+            let r = ranges.next().unwrap_or(&cuboid[cuboid.len()..]);
+            let bounds = r.strip_prefix(axis).ok_or_else(|| ParseError::new(line, column(val, r), r, &format!("{}N..M", axis)))?;
+            let (start, end) = split_field(line, val, bounds, "..", "a range like N..M")?;
+            tmp[i][0] = parse_field(line, val, start, "a number")?;
+            tmp[i][1] = parse_field(line, val, end, "a number")?;
         }
         res.push(Range {
             xr: tmp.remove(0),
             yr: tmp.remove(0),
             zr: tmp.remove(0),
-            action: action
+            action
         });
     }
-    res
+    Ok(res)
 }
 
-fn brute_force_1(input: &Vec<Range>) -> usize {
+pub fn brute_force_1(input: &[Range]) -> usize {
     // 101 x 101 x 101 boolean array to hold all cubes in region
     // Note all coordinates are mapped to 0-100
     // So -50, -50, -50 -> 0, 0, 0
@@ -56,7 +50,7 @@ fn brute_force_1(input: &Vec<Range>) -> usize {
         let val = [r.xr, r.yr, r.zr].concat();
         let mut flag = true;
         for v in val {
-            if v < -50 || v > 50 {
+            if !(-50..=50).contains(&v) {
                 flag = false;
                 break;
             }
@@ -75,7 +69,7 @@ fn brute_force_1(input: &Vec<Range>) -> usize {
             }
         }
     }
-    region.into_iter().map(|v| v.into_iter().flatten().collect::<Vec<usize>>()).flatten().sum()
+    region.into_iter().flat_map(|v| v.into_iter().flatten().collect::<Vec<usize>>()).sum()
 }
 
 // ON - ON -> Add OFF
@@ -96,7 +90,7 @@ fn get_intersection(r1: &Range, r2: &Range) -> Option<Range> {
     }
 }
 
-fn get_total_ones(input: &Vec<Range>) -> i64 {
+fn get_total_ones(input: &[Range]) -> i64 {
     let mut res = 0;
     for range in input {
         if range.action == 1 {
@@ -109,10 +103,9 @@ fn get_total_ones(input: &Vec<Range>) -> i64 {
 // When comparing cuboids, if ON over OFF, or OFF over OFF, don't do anything
 // If ON over ON or OFF over ON, add OFF intersection
 // When comparing cuboid and intersection, if there is an intersection, add intersection with negation of the intersection's action.
-fn count_total(input: &Vec<Range>) -> i64 {
+pub fn count_total(input: &[Range]) -> i64 {
     let mut intersections = vec![];
     for i in 1..input.len() {
-        let cur_action = input[i].action;
         let mut new_intersections = vec![];
         // Check for intersections with all seen intervals
         for j in 0..i {
@@ -134,7 +127,7 @@ fn count_total(input: &Vec<Range>) -> i64 {
         }
         intersections.extend(new_intersections.iter());
     }
-    let mut baseline: i64 = get_total_ones(input);
+    let baseline: i64 = get_total_ones(input);
     let mut net: i64 = 0;
     for val in intersections {
         if val.action == 1 {
@@ -155,31 +148,83 @@ fn count(r: &Range) -> i64 {
     ((r.zr[1] - r.zr[0] + 1) as i64)
 }
 
+/// Reboot steps in order
+pub struct Day22 {
+    steps: Vec<Range>,
+}
+
+impl Solution for Day22 {
+    const DAY: u32 = 22;
+
+    fn parse(input: &str) -> Result<Self, Error> {
+        Ok(Day22 { steps: parse_input(lines(input))? })
+    }
+
+    fn part1(&self) -> Answer {
+        brute_force_1(&self.steps).into()
+    }
+
+    fn part2(&self) -> Answer {
+        count_total(&self.steps).into()
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use aoc_common::{read_lines, Rng};
     #[test]
     fn example() {
-        let input = parse_input(read_input("example").unwrap());
-        //println!("Part1: {}", brute_force_1(&input));
-        //println!("Part2: {}", count_total(&input));
+        let input = parse_input(read_lines("example").unwrap()).unwrap();
+        println!("Part1: {}", brute_force_1(&input));
+        println!("Part2: {}", count_total(&input));
     }
 
+    #[test]
     fn test_intersect() {
-        let input = parse_input(read_input("example2").unwrap());
-        //println!("{:?}", get_intersection(&input[0], &input[1]));
+        let input = parse_input(read_lines("example2").unwrap()).unwrap();
+        println!("{:?}", get_intersection(&input[0], &input[1]));
     }
 
     #[test]
     fn test_algo() {
-        let input = parse_input(read_input("example2").unwrap());
+        let input = parse_input(read_lines("example2").unwrap()).unwrap();
         println!("Part2: example {}", count_total(&input));
     }
 
     #[test]
     fn actual() {
-        let input = parse_input(read_input("input").unwrap());
+        let input = parse_input(read_lines("input").unwrap()).unwrap();
         println!("Part1: {}", brute_force_1(&input));
         println!("Part2: {}", count_total(&input));
     }
+
+    #[test]
+    fn bad_input() {
+        let parse = |line: &str| parse_input(vec![line.to_owned()]).map(|_| ());
+        assert_eq!(parse("on x=-20..26,y=-36..17,z=-47..7"), Ok(()));
+        assert_eq!(parse("of x=-20..26,y=-36..17,z=-47..7"), Err(ParseError::new(1, 1, "of", "on or off")));
+        assert_eq!(parse("on x=-20..26,y=-36..17"), Err(ParseError::new(1, 23, "", "z=N..M")));
+        assert_eq!(parse("on x=-20..26,y=-36.17,z=-47..7"), Err(ParseError::new(1, 16, "-36.17", "a range like N..M")));
+    }
+
+    /// Overlapping cuboids inside the -50..=50 initialization region
+    fn random_steps(rng: &mut Rng) -> Vec<Range> {
+        let axis = |rng: &mut Rng| {
+            let (center, half) = (rng.range(-30..=30) as i32, rng.range(0..=15) as i32);
+            [center - half - rng.range(0..=5) as i32, center + half]
+        };
+        (0..rng.below(8) + 1)
+            .map(|_| Range { xr: axis(rng), yr: axis(rng), zr: axis(rng), action: rng.below(2) })
+            .collect()
+    }
+
+    #[test]
+    fn brute_force_matches_count_total() {
+        let mut rng = Rng::new(22);
+        for _ in 0..1000 {
+            let steps = random_steps(&mut rng);
+            assert_eq!(brute_force_1(&steps) as i64, count_total(&steps), "{:?}", steps);
+        }
+    }
 }
@@ -1,35 +1,31 @@
 use std::collections::{HashMap, BinaryHeap};
 use std::cmp::{Ordering, min, max};
+use aoc_common::{lines, Answer, Error, ParseError, Solution};
 
+/// Amphipod type, from cheapest (A) to most expensive (D) to move
 #[derive(Debug, Copy, Clone, PartialEq, Hash, Eq)]
-enum Pod {
+pub enum Pod {
     A,
     B,
     C,
     D
 }
 
-#[derive(Debug, Eq, Hash, Copy, Clone)]
+#[derive(Debug, PartialEq, Eq, Hash, Copy, Clone)]
 struct State {
     rooms: [Option<Pod>; 8],
     hallway: [Option<Pod>; 7]
 }
 
 impl Ord for State {
-    fn cmp(&self, other: &Self) -> Ordering {
+    fn cmp(&self, _other: &Self) -> Ordering {
         Ordering::Less
     }
 }
 
 impl PartialOrd for State {
     fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
-        Some(Ordering::Less)
-    }
-}
-
-impl PartialEq for State {
-    fn eq(&self, other: &Self) -> bool {
-        (self.rooms, self.hallway) == (other.rooms, other.hallway)
+        Some(self.cmp(other))
     }
 }
 
@@ -41,7 +37,7 @@ impl PartialEq for State {
 // ###D#B#D#B###
 //   #C#A#A#C#
 //   #########
-fn dijkstra(mut start: [Option<Pod>; 8]) -> i64 {
+pub fn dijkstra(start: [Option<Pod>; 8]) -> i64 {
     // Cost to get from Room space to Hallway space
     let hall_cost = [
         [3, 2, 2, 4, 6, 8, 9], // A-Top to Hallway
@@ -112,8 +108,8 @@ fn dijkstra(mut start: [Option<Pod>; 8]) -> i64 {
     // The booleans ensure that the destination is free and the rest of the path is the same
     fn can_move_room_hallway(room_no: usize, hall_no: usize, state: &State, to_room: bool, to_hall: bool) -> bool {
         let mut left;
-        let mut right;
-        if to_room && !state.rooms[room_no].is_none() || to_hall && !state.hallway[hall_no].is_none() {
+        let right;
+        if to_room && state.rooms[room_no].is_some() || to_hall && state.hallway[hall_no].is_some() {
             return false;
         }
         if room_no == 0 || room_no == 1 {
@@ -142,7 +138,7 @@ fn dijkstra(mut start: [Option<Pod>; 8]) -> i64 {
                 if val == hall_no {
                     break;
                 }
-                if !state.hallway[val].is_none() {
+                if state.hallway[val].is_some() {
                     return false;
                 }
             }
@@ -152,25 +148,24 @@ fn dijkstra(mut start: [Option<Pod>; 8]) -> i64 {
                 if val == hall_no {
                     break;
                 }
-                if !state.hallway[val].is_none() {
+                if state.hallway[val].is_some() {
                     return false;
                 }
             }
         }
-        return true;
-    };
+        true
+    }
 
     // Check if room-room move is possible
     // First check if destination room is free
     // Then check if room->temp-hall and temp-hall->room is possible
     let can_move_room_room = |room_no1: usize, room_no2: usize, state: &State| -> bool {
-        let hallway = [2, 3, 4];
         // Check if destination room is empty
-        if !state.rooms[room_no2].is_none() {
+        if state.rooms[room_no2].is_some() {
             return false;
         }
         let (small_room, big_room) = (min(room_no1, room_no2), max(room_no1, room_no2));
-        let mut temp_hall = 0;
+        let temp_hall;
         if small_room <= 1 {
             if big_room <= 3 {
                 temp_hall = 2;
@@ -193,20 +188,20 @@ fn dijkstra(mut start: [Option<Pod>; 8]) -> i64 {
         else {
             temp_hall = 4;
         }
-        return can_move_room_hallway(small_room, temp_hall, state, false, true) && can_move_room_hallway(big_room, temp_hall, state, false, true);
+        can_move_room_hallway(small_room, temp_hall, state, false, true) && can_move_room_hallway(big_room, temp_hall, state, false, true)
     };
 
     let mut state_space: HashMap<State, i64> = HashMap::new();
-    let mut hallway = [None; 7];
-    let start_state = State { rooms: start, hallway: hallway };
-    state_space.insert(start_state.clone(), 0);
+    let hallway = [None; 7];
+    let start_state = State { rooms: start, hallway };
+    state_space.insert(start_state, 0);
     let mut heap = BinaryHeap::new();
-    heap.push((0, start_state.clone()));
+    heap.push((0, start_state));
     let mut min_val = i64::MAX;
 
     while !heap.is_empty() {
         let (cost, state) = heap.pop().unwrap();
-        let cost = -1 * cost; // Since this is a max-heap we need to negate
+        let cost = -cost; // Since this is a max-heap we need to negate
         if reached_end(&state.rooms) {
             min_val = min(min_val, cost);
             continue;
@@ -225,17 +220,17 @@ fn dijkstra(mut start: [Option<Pod>; 8]) -> i64 {
                 if !can_move_room_room(i, room_no, &state) {
                     continue;
                 }
-                let mut new_rooms = state.rooms.clone();
+                let mut new_rooms = state.rooms;
                 new_rooms[i] = None;
                 new_rooms[room_no] = *room;
                 let num_steps = room_cost[i][room_no];
                 let add_cost = cost + num_steps * get_cost(&room.unwrap());
 
-                let new_state = State { rooms: new_rooms, hallway: state.hallway.clone() };
+                let new_state = State { rooms: new_rooms, hallway: state.hallway };
                 let ch = state_space.entry(new_state).or_insert(i64::MAX);
                 if *ch > add_cost {
                     *ch = add_cost;
-                    heap.push((-1 * add_cost, new_state));
+                    heap.push((-add_cost, new_state));
                 }
             }
         }
@@ -249,9 +244,9 @@ fn dijkstra(mut start: [Option<Pod>; 8]) -> i64 {
                 if !can_move_room_hallway(room_no, hall_no, &state, true, false) {
                     continue;
                 }
-                let mut new_rooms = state.rooms.clone();
+                let mut new_rooms = state.rooms;
                 new_rooms[room_no] = *hall;
-                let mut new_hallway = state.hallway.clone();
+                let mut new_hallway = state.hallway;
                 new_hallway[hall_no] = None;
                 let num_steps = hall_cost[room_no][hall_no];
                 let add_cost = cost + num_steps * get_cost(&hall.unwrap());
@@ -260,7 +255,7 @@ fn dijkstra(mut start: [Option<Pod>; 8]) -> i64 {
                 let ch = state_space.entry(new_state).or_insert(i64::MAX);
                 if *ch > add_cost {
                     *ch = add_cost;
-                    heap.push((-1 * add_cost, new_state));
+                    heap.push((-add_cost, new_state));
                 }
             }
         }
@@ -270,13 +265,13 @@ fn dijkstra(mut start: [Option<Pod>; 8]) -> i64 {
             if room.is_none() || right_bottom_room(&room.unwrap(), room_no) {
                 continue;
             }
-            for (hall_no, hall) in state.hallway.iter().enumerate() {
+            for hall_no in 0..state.hallway.len() {
                 if !can_move_room_hallway(room_no, hall_no, &state, false, true) {
                     continue;
                 }
-                let mut new_rooms = state.rooms.clone();
+                let mut new_rooms = state.rooms;
                 new_rooms[room_no] = None;
-                let mut new_hallway = state.hallway.clone();
+                let mut new_hallway = state.hallway;
                 new_hallway[hall_no] = *room;
                 let num_steps = hall_cost[room_no][hall_no];
                 let add_cost = cost + num_steps * get_cost(&room.unwrap());
@@ -285,7 +280,7 @@ fn dijkstra(mut start: [Option<Pod>; 8]) -> i64 {
                 let ch = state_space.entry(new_state).or_insert(i64::MAX);
                 if *ch > add_cost {
                     *ch = add_cost;
-                    heap.push((-1 * add_cost, new_state));
+                    heap.push((-add_cost, new_state));
                 }
             }
         }
@@ -293,16 +288,66 @@ fn dijkstra(mut start: [Option<Pod>; 8]) -> i64 {
     min_val
 }
 
+/// Read the starting rooms from the burrow diagram, in the order used by `dijkstra`
+pub fn parse_input(input: &[String]) -> Result<[Option<Pod>; 8], ParseError> {
+    let mut rows = vec![];
+    for i in 2..4 {
+        let row = input.get(i).map_or("", |v| v.as_str());
+        let mut pods = vec![];
+        for (j, c) in row.char_indices() {
+            match c {
+                'A' => pods.push(Pod::A),
+                'B' => pods.push(Pod::B),
+                'C' => pods.push(Pod::C),
+                'D' => pods.push(Pod::D),
+                '#' | ' ' => (),
+                _ => return Err(ParseError::new(i + 1, j + 1, &row[j..j + c.len_utf8()], "an amphipod A-D or a wall")),
+            }
+        }
+        if pods.len() != 4 {
+            return Err(ParseError::new(i + 1, 1, row, "four amphipods"));
+        }
+        rows.push(pods);
+    }
+    let mut start = [None; 8];
+    for room in 0..4 {
+        start[2 * room] = Some(rows[0][room]);
+        start[2 * room + 1] = Some(rows[1][room]);
+    }
+    Ok(start)
+}
+
+/// Starting rooms of the amphipods
+pub struct Day23 {
+    start: [Option<Pod>; 8],
+}
+
+impl Solution for Day23 {
+    const DAY: u32 = 23;
+
+    fn parse(input: &str) -> Result<Self, Error> {
+        Ok(Day23 { start: parse_input(&lines(input))? })
+    }
+
+    fn part1(&self) -> Answer {
+        dijkstra(self.start).into()
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use aoc_common::read_lines;
     #[test]
     fn example() {
-        println!("Part1: {}", dijkstra([Some(Pod::B), Some(Pod::A), Some(Pod::C), Some(Pod::D), Some(Pod::B), Some(Pod::C), Some(Pod::D), Some(Pod::A)]));
+        let start = parse_input(&read_lines("example").unwrap()).unwrap();
+        assert_eq!(start, [Some(Pod::B), Some(Pod::A), Some(Pod::C), Some(Pod::D), Some(Pod::B), Some(Pod::C), Some(Pod::D), Some(Pod::A)]);
+        println!("Part1: {}", dijkstra(start));
     }
 
     #[test]
     fn actual() {
-        println!("Part1: {}", dijkstra([Some(Pod::D), Some(Pod::C), Some(Pod::B), Some(Pod::A), Some(Pod::D), Some(Pod::A), Some(Pod::B), Some(Pod::C)]));
+        let start = parse_input(&read_lines("input").unwrap()).unwrap();
+        println!("Part1: {}", dijkstra(start));
     }
 }
@@ -0,0 +1,414 @@
+use std::fmt;
+use aoc_common::{column, lines, parse_field, Answer, Error, ParseError, Solution};
+
+const REGISTERS: [char; 4] = ['w', 'x', 'y', 'z'];
+
+/// Second argument of an instruction
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum Operand {
+    /// Index into w, x, y, z
+    Reg(usize),
+    Num(i64),
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum Instruction {
+    Inp(usize),
+    Add(usize, Operand),
+    Mul(usize, Operand),
+    Div(usize, Operand),
+    Mod(usize, Operand),
+    Eql(usize, Operand),
+}
+
+impl fmt::Display for Operand {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Operand::Reg(r) => write!(f, "{}", REGISTERS[*r]),
+            Operand::Num(n) => write!(f, "{}", n),
+        }
+    }
+}
+
+impl fmt::Display for Instruction {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let (name, a, b) = match *self {
+            Instruction::Inp(a) => return write!(f, "inp {}", REGISTERS[a]),
+            Instruction::Add(a, b) => ("add", a, b),
+            Instruction::Mul(a, b) => ("mul", a, b),
+            Instruction::Div(a, b) => ("div", a, b),
+            Instruction::Mod(a, b) => ("mod", a, b),
+            Instruction::Eql(a, b) => ("eql", a, b),
+        };
+        write!(f, "{} {} {}", name, REGISTERS[a], b)
+    }
+}
+
+pub fn parse_input(input: &[String]) -> Result<Vec<Instruction>, ParseError> {
+    input.iter()
+        .enumerate()
+        .filter(|(_, val)| !val.trim().is_empty())
+        .map(|(i, val)| {
+            let fields: Vec<&str> = val.split_whitespace().collect();
+            let register = |s: &str| match REGISTERS.iter().position(|&r| s.len() == 1 && s.starts_with(r)) {
+                Some(r) => Ok(r),
+                None => Err(ParseError::new(i + 1, column(val, s), s, "w, x, y or z")),
+            };
+            let arity = if fields.first() == Some(&"inp") { 2 } else { 3 };
+            if fields.len() != arity {
+                let expected = if arity == 2 { "inp a" } else { "an instruction like add a b" };
+                return Err(ParseError::new(i + 1, 1, val, expected));
+            }
+            let a = register(fields[1])?;
+            if arity == 2 {
+                return Ok(Instruction::Inp(a));
+            }
+            let b = match register(fields[2]) {
+                Ok(r) => Operand::Reg(r),
+                Err(_) => Operand::Num(parse_field(i + 1, val, fields[2], "a register or number")?),
+            };
+            match fields[0] {
+                "add" => Ok(Instruction::Add(a, b)),
+                "mul" => Ok(Instruction::Mul(a, b)),
+                "div" => Ok(Instruction::Div(a, b)),
+                "mod" => Ok(Instruction::Mod(a, b)),
+                "eql" => Ok(Instruction::Eql(a, b)),
+                op => Err(ParseError::new(i + 1, column(val, op), op, "inp, add, mul, div, mod or eql")),
+            }
+        })
+        .collect()
+}
+
+/// Why the ALU stopped before the end of the program
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum Fault {
+    /// `inp` ran with no input left
+    MissingInput,
+    /// `div` or `mod` by zero, or `mod` by a negative number
+    DivideByZero,
+    /// `mod` of a negative number
+    NegativeModulo,
+    /// A result that does not fit in 64 bits
+    Overflow,
+}
+
+/// Run `program` on `input` from all-zero registers. Returns w, x, y and z.
+pub fn run(program: &[Instruction], input: &[i64]) -> Result<[i64; 4], Fault> {
+    let mut regs = [0; 4];
+    let mut input = input.iter();
+    for instruction in program {
+        let value = |b: Operand, regs: &[i64; 4]| match b {
+            Operand::Reg(r) => regs[r],
+            Operand::Num(n) => n,
+        };
+        match *instruction {
+            Instruction::Inp(a) => regs[a] = *input.next().ok_or(Fault::MissingInput)?,
+            Instruction::Add(a, b) => regs[a] = regs[a].checked_add(value(b, &regs)).ok_or(Fault::Overflow)?,
+            Instruction::Mul(a, b) => regs[a] = regs[a].checked_mul(value(b, &regs)).ok_or(Fault::Overflow)?,
+            Instruction::Div(a, b) => {
+                let b = value(b, &regs);
+                if b == 0 {
+                    return Err(Fault::DivideByZero);
+                }
+                // Integer division in Rust already truncates towards zero like the ALU
+                regs[a] = regs[a].checked_div(b).ok_or(Fault::Overflow)?;
+            }
+            Instruction::Mod(a, b) => {
+                let b = value(b, &regs);
+                if b <= 0 {
+                    return Err(Fault::DivideByZero);
+                }
+                if regs[a] < 0 {
+                    return Err(Fault::NegativeModulo);
+                }
+                regs[a] %= b;
+            }
+            Instruction::Eql(a, b) => regs[a] = (regs[a] == value(b, &regs)) as i64,
+        }
+    }
+    Ok(regs)
+}
+
+/// The 18 instructions repeated for every digit of MONAD, with `N` for the
+/// three numbers that change from block to block
+const BLOCK: [&str; 18] = [
+    "inp w", "mul x 0", "add x z", "mod x 26", "div z N", "add x N", "eql x w", "eql x 0", "mul y 0",
+    "add y 25", "mul y x", "add y 1", "mul z y", "mul y 0", "add y w", "add y N", "mul y x", "add z y",
+];
+
+/// The numbers that differ between blocks. With `z` used as a stack of
+/// base 26 digits, each block either pushes `digit + offset` (`divisor` 1),
+/// or pops the top value and pushes again unless `top + check == digit`
+/// (`divisor` 26).
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct Block {
+    pub divisor: i64,
+    pub check: i64,
+    pub offset: i64,
+}
+
+/// Split a program into blocks of the puzzle's shape and read off their numbers
+pub fn blocks(program: &[Instruction], input: &[String]) -> Result<Vec<Block>, ParseError> {
+    // Line of every instruction, as blank lines were skipped
+    let line_nos: Vec<usize> = (0..input.len()).filter(|&i| !input[i].trim().is_empty()).collect();
+    if program.is_empty() || !program.len().is_multiple_of(BLOCK.len()) {
+        let i = line_nos.get(program.len() / BLOCK.len() * BLOCK.len()).copied().unwrap_or(input.len());
+        return Err(ParseError::new(i + 1, 1, input.get(i).map_or("", |s| s.as_str()), "a block of 18 instructions"));
+    }
+    let blocks: Vec<Block> = program.chunks(BLOCK.len())
+        .enumerate()
+        .map(|(k, chunk)| {
+            let mut numbers = vec![];
+            for (j, (instruction, expected)) in chunk.iter().zip(BLOCK.iter()).enumerate() {
+                let i = line_nos[k * BLOCK.len() + j];
+                let text = instruction.to_string();
+                let matches = match expected.strip_suffix(" N") {
+                    Some(prefix) => match instruction {
+                        Instruction::Div(_, Operand::Num(n)) | Instruction::Add(_, Operand::Num(n)) if text.starts_with(prefix) => {
+                            numbers.push(*n);
+                            true
+                        }
+                        _ => false,
+                    },
+                    None => text == *expected,
+                };
+                if !matches {
+                    return Err(ParseError::new(i + 1, 1, &input[i], expected));
+                }
+            }
+            let block = Block { divisor: numbers[0], check: numbers[1], offset: numbers[2] };
+            match block.divisor {
+                1 | 26 => Ok(block),
+                _ => {
+                    let i = line_nos[k * BLOCK.len() + 4];
+                    Err(ParseError::new(i + 1, 1, &input[i], "div z 1 or div z 26"))
+                }
+            }
+        })
+        .collect::<Result<_, _>>()?;
+
+    // Only programs where every value is pushed unconditionally and popped
+    // by a later block can be solved by pairing blocks, so reject the rest
+    // rather than report that no number is valid
+    let unsupported = |k: usize, j: usize, expected: &str| {
+        let i = line_nos[k * BLOCK.len() + j];
+        Err(ParseError::new(i + 1, 1, &input[i], &format!("{}, other programs are unsupported", expected)))
+    };
+    let mut stack = vec![];
+    for (k, block) in blocks.iter().enumerate() {
+        // Pushed values stay single base 26 digits, and none is zero
+        if !(0..=16).contains(&block.offset) {
+            return unsupported(k, 15, "add y N with N from 0 to 16");
+        }
+        match block.divisor {
+            // A check of 10 or more can never match a digit, so the block always pushes
+            1 if block.check < 10 => return unsupported(k, 5, "add x N with N of at least 10 after div z 1"),
+            1 => stack.push(k),
+            _ if stack.pop().is_none() => return unsupported(k, 4, "div z 1, as there is no pushed value to pop"),
+            _ => (),
+        }
+    }
+    match stack.first() {
+        Some(&k) => unsupported(k, 4, "a later div z 26 to pop each pushed value"),
+        None => Ok(blocks),
+    }
+}
+
+/// Digits of the largest or smallest model number MONAD accepts, most
+/// significant first, if any. Every pushing block is paired with the
+/// popping block that removes its value, which fixes the difference
+/// between their two digits; each pair is then set independently. The
+/// number is accepted exactly when every pair matches, for the programs
+/// `blocks` lets through.
+pub fn model_number(blocks: &[Block], largest: bool) -> Option<Vec<i64>> {
+    let mut digits = vec![0; blocks.len()];
+    let mut stack = vec![];
+    for (i, block) in blocks.iter().enumerate() {
+        if block.divisor == 1 {
+            stack.push((i, block.offset));
+            continue;
+        }
+        let (j, offset) = stack.pop().expect("blocks checks that pushes and pops pair up");
+        // digits[i] == digits[j] + diff
+        let diff = offset + block.check;
+        let first = match largest {
+            true => 9.min(9 - diff),
+            false => 1.max(1 - diff),
+        };
+        if !(1..=9).contains(&first) || !(1..=9).contains(&(first + diff)) {
+            return None;
+        }
+        digits[j] = first;
+        digits[i] = first + diff;
+    }
+    Some(digits)
+}
+
+/// The MONAD program and the numbers of each of its blocks
+pub struct Day24 {
+    program: Vec<Instruction>,
+    blocks: Vec<Block>,
+}
+
+impl Day24 {
+    fn solve(&self, largest: bool) -> Answer {
+        match model_number(&self.blocks, largest) {
+            Some(digits) => {
+                debug_assert_eq!(run(&self.program, &digits).map(|regs| regs[3]), Ok(0));
+                // As text, since a long enough program has more digits than any integer holds
+                digits.iter().map(|d| d.to_string()).collect::<String>().into()
+            }
+            None => "no model number is valid".into(),
+        }
+    }
+}
+
+impl Solution for Day24 {
+    const DAY: u32 = 24;
+
+    fn parse(input: &str) -> Result<Self, Error> {
+        let input = lines(input);
+        let program = parse_input(&input)?;
+        let blocks = blocks(&program, &input)?;
+        Ok(Day24 { program, blocks })
+    }
+
+    fn part1(&self) -> Answer {
+        self.solve(true)
+    }
+
+    fn part2(&self) -> Answer {
+        self.solve(false)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use aoc_common::{read_lines, Rng};
+    #[test]
+    fn example() {
+        // Binary digits of the input, lowest in z
+        let program = parse_input(&read_lines("example").unwrap()).unwrap();
+        assert_eq!(run(&program, &[13]), Ok([1, 1, 0, 1]));
+        assert_eq!(run(&program, &[6]), Ok([0, 1, 1, 0]));
+        assert_eq!(run(&program, &[]), Err(Fault::MissingInput));
+    }
+
+    #[test]
+    fn faults() {
+        let program = |text: &str| parse_input(&text.lines().map(|s| s.to_owned()).collect::<Vec<String>>()).unwrap();
+        // z to the 32nd power
+        let squaring = program("inp z\nmul z z\nmul z z\nmul z z\nmul z z\nmul z z");
+        assert_eq!(run(&squaring, &[2]), Ok([0, 0, 0, 1 << 32]));
+        assert_eq!(run(&squaring, &[9]), Err(Fault::Overflow));
+        assert_eq!(run(&program("inp w\nadd w 1"), &[i64::MAX]), Err(Fault::Overflow));
+        assert_eq!(run(&program("inp w\ninp x\ndiv w x"), &[i64::MIN, -1]), Err(Fault::Overflow));
+        assert_eq!(run(&program("inp w\ndiv w x"), &[5]), Err(Fault::DivideByZero));
+        assert_eq!(run(&program("inp w\nmod w x"), &[5]), Err(Fault::DivideByZero));
+        assert_eq!(run(&program("inp w\nmod w 2"), &[-5]), Err(Fault::NegativeModulo));
+    }
+
+    #[test]
+    fn actual() {
+        let input = read_lines("input").unwrap();
+        let program = parse_input(&input).unwrap();
+        let blocks = blocks(&program, &input).unwrap();
+        let largest = model_number(&blocks, true).unwrap();
+        let smallest = model_number(&blocks, false).unwrap();
+        println!("Part1: {:?}", largest);
+        println!("Part2: {:?}", smallest);
+        assert_eq!(run(&program, &largest).unwrap()[3], 0);
+        assert_eq!(run(&program, &smallest).unwrap()[3], 0);
+    }
+
+    /// The pairing argument agrees with running the program: no number
+    /// outside [smallest, largest] is accepted, checked on random numbers
+    /// near the bounds
+    #[test]
+    fn model_numbers_match_interpreter() {
+        let input = read_lines("input").unwrap();
+        let program = parse_input(&input).unwrap();
+        let blocks = blocks(&program, &input).unwrap();
+        let (largest, smallest) = (model_number(&blocks, true).unwrap(), model_number(&blocks, false).unwrap());
+        let mut rng = Rng::new(24);
+        for _ in 0..2000 {
+            let mut number = if rng.flip() { largest.clone() } else { smallest.clone() };
+            let k = rng.below(number.len());
+            number[k] = rng.range(1..=9);
+            let accepted = run(&program, &number).unwrap()[3] == 0;
+            // Digit lists of the same length compare like the numbers
+            assert!(!accepted || (smallest <= number && number <= largest), "{:?} accepted", number);
+        }
+    }
+
+    #[test]
+    fn bad_input() {
+        let input = vec!["inp w".to_owned(), "add x 2".to_owned(), "sub x 1".to_owned()];
+        assert_eq!(parse_input(&input), Err(ParseError::new(3, 1, "sub", "inp, add, mul, div, mod or eql")));
+        let input = vec!["inp q".to_owned()];
+        assert_eq!(parse_input(&input), Err(ParseError::new(1, 5, "q", "w, x, y or z")));
+        let input = vec!["add x".to_owned()];
+        assert_eq!(parse_input(&input), Err(ParseError::new(1, 1, "add x", "an instruction like add a b")));
+
+        let mut input = read_lines("input").unwrap();
+        input[22] = "div z 3".to_owned();
+        let program = parse_input(&input).unwrap();
+        assert_eq!(blocks(&program, &input), Err(ParseError::new(23, 1, "div z 3", "div z 1 or div z 26")));
+        input[22] = "add y 25".to_owned();
+        let program = parse_input(&input).unwrap();
+        assert_eq!(blocks(&program, &input), Err(ParseError::new(23, 1, "add y 25", "div z N")));
+    }
+
+    /// The program text for `blocks`
+    fn monad(blocks: &[Block]) -> Vec<String> {
+        blocks.iter()
+            .flat_map(|b| {
+                let mut numbers = vec![b.divisor, b.check, b.offset].into_iter();
+                BLOCK.iter().map(move |line| match line.strip_suffix('N') {
+                    Some(prefix) => format!("{}{}", prefix, numbers.next().unwrap()),
+                    None => line.to_string(),
+                })
+            })
+            .collect()
+    }
+
+    #[test]
+    fn unsupported_programs() {
+        let check = |blocks: &[Block]| {
+            let input = monad(blocks);
+            super::blocks(&parse_input(&input).unwrap(), &input)
+        };
+        let push = |check, offset| Block { divisor: 1, check, offset };
+        let pop = |check, offset| Block { divisor: 26, check, offset };
+        let unsupported = |line, text: &str, expected: &str| {
+            Err(ParseError::new(line, 1, text, &format!("{}, other programs are unsupported", expected)))
+        };
+        // A pushing block whose check can match, e.g. 99 is valid here
+        assert_eq!(check(&[push(5, 3), pop(-3, 4)]),
+            unsupported(6, "add x 5", "add x N with N of at least 10 after div z 1"));
+        assert_eq!(check(&[pop(-3, 4)]), unsupported(5, "div z 26", "div z 1, as there is no pushed value to pop"));
+        assert_eq!(check(&[push(12, 3), push(12, 3), pop(-3, 4)]),
+            unsupported(5, "div z 1", "a later div z 26 to pop each pushed value"));
+        assert_eq!(check(&[push(12, 3), pop(-3, 20)]), unsupported(34, "add y 20", "add y N with N from 0 to 16"));
+
+        // Pairs that can never match leave no valid number
+        let blocks = check(&[push(12, 3), pop(-13, 4)]).unwrap();
+        assert_eq!(model_number(&blocks, true), None);
+    }
+
+    #[test]
+    fn long_programs() {
+        // Twenty digits, more than a u64 holds
+        let blocks: Vec<Block> = (0..10).map(|k| Block { divisor: 1, check: 10, offset: k })
+            .chain((0..10).rev().map(|k| Block { divisor: 26, check: -k, offset: 0 }))
+            .collect();
+        let input = monad(&blocks).join("\n");
+        let day = Day24::parse(&input).unwrap();
+        assert_eq!(day.part1().to_string(), "99999999999999999999");
+        assert_eq!(day.part2().to_string(), "11111111111111111111");
+        assert_eq!(run(&day.program, &[9; 20]).unwrap()[3], 0);
+        // The last pop fails, so it pushes its own digit back
+        assert_eq!(run(&day.program, &[[9; 19].as_slice(), &[8]].concat()).unwrap()[3], 8);
+    }
+}
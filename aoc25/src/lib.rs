@@ -0,0 +1,194 @@
+use aoc_common::{lines, Answer, Cell, Error, Grid, ParseError, Solution};
+
+/// One location on the sea floor
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum Spot {
+    Empty,
+    /// Part of the east-facing herd, `>`
+    East,
+    /// Part of the south-facing herd, `v`
+    South,
+}
+
+impl Cell for Spot {
+    const EXPECTED: &'static str = "'>', 'v' or '.'";
+
+    fn from_char(c: char) -> Option<Self> {
+        match c {
+            '.' => Some(Spot::Empty),
+            '>' => Some(Spot::East),
+            'v' => Some(Spot::South),
+            _ => None,
+        }
+    }
+
+    fn to_char(&self) -> char {
+        match self {
+            Spot::Empty => '.',
+            Spot::East => '>',
+            Spot::South => 'v',
+        }
+    }
+}
+
+pub fn parse_input(input: &[String]) -> Result<Grid<Spot>, ParseError> {
+    Grid::from_lines(input)
+}
+
+/// Move every member of `herd` that faces an empty spot, all at once.
+/// Herds leaving one edge come back in on the opposite edge.
+fn move_herd(grid: &Grid<Spot>, herd: Spot) -> (Grid<Spot>, usize) {
+    let (height, width) = (grid.height(), grid.width());
+    let ahead = |(i, j): (usize, usize)| match herd {
+        Spot::East => (i, (j + 1) % width),
+        _ => ((i + 1) % height, j),
+    };
+    let mut next = grid.clone();
+    let mut moved = 0;
+    for pos in grid.positions() {
+        if grid[pos] == herd && grid[ahead(pos)] == Spot::Empty {
+            next[pos] = Spot::Empty;
+            next[ahead(pos)] = herd;
+            moved += 1;
+        }
+    }
+    (next, moved)
+}
+
+/// One step: the east-facing herd moves, then the south-facing herd.
+/// Returns the new state and the number of sea cucumbers that moved.
+pub fn step(grid: &Grid<Spot>) -> (Grid<Spot>, usize) {
+    let (grid, east) = move_herd(grid, Spot::East);
+    let (grid, south) = move_herd(&grid, Spot::South);
+    (grid, east + south)
+}
+
+/// States after each step, ending with the first step in which nothing
+/// moves. Herds that never stop come back to an earlier state and cycle
+/// forever, so the states also end once such a repeat is seen.
+pub struct Steps {
+    grid: Grid<Spot>,
+    done: bool,
+    cycles: bool,
+    /// State to compare against, replaced after twice as many steps each
+    /// time (Brent's algorithm), which finds a cycle without keeping every state
+    checkpoint: Grid<Spot>,
+    since: usize,
+    power: usize,
+}
+
+impl Steps {
+    /// Whether the herds were found to cycle without ever standing still
+    pub fn cycles(&self) -> bool {
+        self.cycles
+    }
+}
+
+impl Iterator for Steps {
+    type Item = Grid<Spot>;
+
+    fn next(&mut self) -> Option<Grid<Spot>> {
+        if self.done {
+            return None;
+        }
+        let (grid, moved) = step(&self.grid);
+        self.grid = grid;
+        self.cycles = moved > 0 && self.grid == self.checkpoint;
+        self.done = moved == 0 || self.cycles;
+        self.since += 1;
+        if self.since == self.power {
+            self.checkpoint = self.grid.clone();
+            self.power *= 2;
+            self.since = 0;
+        }
+        Some(self.grid.clone())
+    }
+}
+
+pub fn steps(grid: &Grid<Spot>) -> Steps {
+    Steps { grid: grid.clone(), done: false, cycles: false, checkpoint: grid.clone(), since: 0, power: 1 }
+}
+
+/// Number of the first step in which no sea cucumber moves, `None` if the
+/// herds keep moving forever
+pub fn first_still_step(grid: &Grid<Spot>) -> Option<usize> {
+    let mut steps = steps(grid);
+    let n = steps.by_ref().count();
+    match steps.cycles() {
+        true => None,
+        false => Some(n),
+    }
+}
+
+/// The two herds on the sea floor
+pub struct Day25 {
+    grid: Grid<Spot>,
+}
+
+impl Solution for Day25 {
+    const DAY: u32 = 25;
+
+    fn parse(input: &str) -> Result<Self, Error> {
+        Ok(Day25 { grid: parse_input(&lines(input))? })
+    }
+
+    fn part1(&self) -> Answer {
+        match first_still_step(&self.grid) {
+            Some(n) => n.into(),
+            None => "the herds never stop moving".into(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use aoc_common::read_lines;
+    #[test]
+    fn example() {
+        let grid = parse_input(&read_lines("example").unwrap()).unwrap();
+        println!("Part1: {:?}", first_still_step(&grid));
+        assert_eq!(first_still_step(&grid), Some(58));
+        let last = steps(&grid).last().unwrap();
+        assert_eq!(step(&last), (last.clone(), 0));
+        print!("{}", last);
+    }
+
+    #[test]
+    fn actual() {
+        let grid = parse_input(&read_lines("input").unwrap()).unwrap();
+        println!("Part1: {:?}", first_still_step(&grid));
+    }
+
+    #[test]
+    fn wrap_around() {
+        let grid: Grid<Spot> = "..>\nv..\n..v".parse().unwrap();
+        let states: Vec<String> = steps(&grid).take(2).map(|g| g.to_string()).collect();
+        // The east herd moves first, so the bottom `v` can wrap onto the spot the `>` just left
+        assert_eq!(states, vec![">.v\n...\nv..\n", "v>.\n..v\n...\n"]);
+    }
+
+    #[test]
+    fn cycling() {
+        // A lone `>` goes round its row forever
+        let grid: Grid<Spot> = ">..\n...".parse().unwrap();
+        assert_eq!(first_still_step(&grid), None);
+        let mut states = steps(&grid);
+        assert!(states.by_ref().count() <= 6 && states.cycles());
+        assert_eq!(Day25 { grid }.part1().to_string(), "the herds never stop moving");
+
+        // One of each herd circling, in rows and columns of their own
+        let grid: Grid<Spot> = "v....\n.....\n.>...\n.....\n.....".parse().unwrap();
+        assert_eq!(first_still_step(&grid), None);
+        // Blocked herds still stand still
+        let grid: Grid<Spot> = ">>\nvv".parse().unwrap();
+        assert_eq!(first_still_step(&grid), Some(1));
+    }
+
+    #[test]
+    fn bad_input() {
+        let mut input = read_lines("example").unwrap();
+        input[1].replace_range(3..4, "<");
+        assert_eq!(parse_input(&input), Err(ParseError::new(2, 4, "<", "'>', 'v' or '.'")));
+    }
+}
@@ -1,104 +1,134 @@
-use std::io::{BufReader, Error, ErrorKind};
-use std::io::prelude::*;
-use std::fs::File;
-
-fn read_input(filename: &str) -> Result<Vec<String>, Error> {
-    let f = File::open(filename).unwrap();
-    let f = BufReader::new(f);
-    f.lines().map(|l| l.and_then(|v| v.parse().map_err(|e| Error::new(ErrorKind::InvalidData, e)))).collect()
+use aoc_common::{lines, Answer, Error, ParseError, Solution};
+
+mod trie;
+pub use trie::{Criterion, Tie, TieBreak, Trie};
+
+/// Diagnostic report with every line packed into a word, first digit in
+/// the most significant of the `width` low bits
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Report {
+    pub width: u32,
+    pub words: Vec<u64>,
 }
 
-/// Compute the most common bit in each position
-/// If 1 is most common, res[i] > 0
-/// If 0 is most common, res[i] < 0
-/// Equally matched, res[i] = 0
-fn compute_dominant_bit(input: &Vec<String>, mask: &Vec<bool>) -> Vec<i32> {
-    let mut res: Vec<i32> = vec![0; input[0].len()];
-    for (i, val) in input.iter().enumerate() {
-        if !mask[i] { continue }; // If mask is not set at current index, don't consider the number
-        for (index, digit) in val.chars().enumerate() {
-            match digit {
-                '0' => res[index] -= 1,
-                '1' => res[index] += 1,
-                _ => (),
-            }
-        }
+impl Report {
+    /// Mask of the `width` low bits
+    pub fn mask(&self) -> u64 {
+        u64::MAX >> (64 - self.width)
     }
-    res
 }
 
-fn compute_rating(input: &Vec<String>, oxygen: bool) -> u64 {
-    let mut mask = vec![true; input.len()]; // Start computation with all numbers included, progresively whittled down
-    let new_input: Vec<&[u8]> = input.iter().map(|val| val.as_bytes()).collect::<Vec<&[u8]>>();
-    let mut j: usize = 0; // Current bit index
-    while mask.iter().map(|val| if *val {1} else {0}).sum::<u32>() > 1 {
-        let res = compute_dominant_bit(input, &mask);
-        for i in 0..mask.len() {
-            let val = mask[i];
-            if oxygen && val && ( (new_input[i][j] == b'1' && res[j] < 0) || (new_input[i][j] == b'0' && res[j] >= 0) ) {
-                mask[i] = !mask[i]
-            }
-            if !oxygen && val && ( (new_input[i][j] == b'1' && res[j] >= 0) || (new_input[i][j] == b'0' && res[j] < 0) ) {
-                mask[i] = !mask[i];
-            }
-        }
-        j += 1;
+/// Parse a non-empty report of binary numbers of the same width, at most 64 bits
+pub fn parse_report(input: &[String]) -> Result<Report, ParseError> {
+    let width = match input.first() {
+        Some(first) if !first.is_empty() => first.len(),
+        _ => return Err(ParseError::new(1, 1, "", "a binary number")),
+    };
+    if width > 64 {
+        return Err(ParseError::new(1, 1, &input[0], "at most 64 bits"));
     }
-    let mut rating: u64 = 0;
-    for (i, val) in mask.iter().enumerate() {
-        if *val {
-            for c in new_input[i].iter() {
-                match c {
-                    b'0' => rating = rating << 1,
-                    b'1' => rating = (rating << 1) | 1,
-                    _ => (),
-                }
-            }
+    let mut words = Vec::with_capacity(input.len());
+    for (i, line) in input.iter().enumerate() {
+        if let Some(col) = line.find(|c| c != '0' && c != '1') {
+            return Err(ParseError::new(i + 1, col + 1, &line[col..], "only 0 and 1"));
         }
+        if line.len() != width {
+            let expected = format!("{} bits", width);
+            return Err(ParseError::new(i + 1, 1, line, &expected));
+        }
+        words.push(line.bytes().fold(0, |word, b| (word << 1) | (b - b'0') as u64));
     }
-    rating
+    Ok(Report { width: width as u32, words })
 }
 
-fn compute_power(input: &Vec<String>) -> u64 {
-    let res = compute_dominant_bit(input, &vec![true; input.len()]);
-    let mut gamma: u64 = 0;
-    let mut epsilon: u64 = 0;
-
-    for digit in res.iter() {
-        match *digit >= 0 {
-            true => {
-                gamma = (gamma << 1) | 1;
-                epsilon = epsilon << 1;
-            },
-            false => {
-                gamma = gamma << 1;
-                epsilon = (epsilon << 1) | 1;
-            },
-        }
-    }
-    gamma * epsilon
+/// Whether at least half of `words` have `bit` set
+fn ones_dominate(words: &[u64], bit: u32) -> bool {
+    let ones = words.iter().filter(|&&w| (w >> bit) & 1 == 1).count();
+    2 * ones >= words.len()
 }
 
-fn compute_life_support_rating(input: &Vec<String>) -> u64 {
-    let oxygen = compute_rating(input, true);
-    let carbon = compute_rating(input, false);
-    oxygen * carbon
+/// Gamma rate (the most common bit of each position, 1 on ties) and
+/// epsilon rate (its complement)
+pub fn gamma_epsilon(report: &Report) -> (u64, u64) {
+    let gamma = (0..report.width)
+        .filter(|&bit| ones_dominate(&report.words, bit))
+        .fold(0, |gamma, bit| gamma | 1 << bit);
+    (gamma, !gamma & report.mask())
+}
+
+pub fn compute_power(report: &Report) -> u128 {
+    let (gamma, epsilon) = gamma_epsilon(report);
+    gamma as u128 * epsilon as u128
+}
+
+/// Oxygen generator rating times CO2 scrubber rating, with the puzzle's
+/// tie-breaks: keep 1s for oxygen and 0s for CO2
+pub fn compute_life_support_rating(report: &Report) -> u128 {
+    let trie = Trie::new(report);
+    let oxygen = trie.rating(Criterion::MostCommon, TieBreak::PreferOne).unwrap();
+    let carbon = trie.rating(Criterion::LeastCommon, TieBreak::PreferZero).unwrap();
+    oxygen as u128 * carbon as u128
+}
+
+/// Diagnostic report, one binary number per line
+pub struct Day3 {
+    report: Report,
+}
+
+impl Solution for Day3 {
+    const DAY: u32 = 3;
+
+    fn parse(input: &str) -> Result<Self, Error> {
+        Ok(Day3 { report: parse_report(&lines(input))? })
+    }
+
+    fn part1(&self) -> Answer {
+        compute_power(&self.report).into()
+    }
+
+    fn part2(&self) -> Answer {
+        compute_life_support_rating(&self.report).into()
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use aoc_common::read_lines;
     #[test]
     fn example() {
-        let input = read_input("example").unwrap();
+        let input = parse_report(&read_lines("example").unwrap()).unwrap();
         println!("Part1: {}", compute_power(&input));
         println!("Part2: {}", compute_life_support_rating(&input));
     }
 
     #[test]
     fn actual() {
-        let input = read_input("input").unwrap();
+        let input = parse_report(&read_lines("input").unwrap()).unwrap();
         println!("Part1: {}", compute_power(&input));
         println!("Part2: {}", compute_life_support_rating(&input));
     }
+
+    #[test]
+    fn packed() {
+        let input = parse_report(&read_lines("example").unwrap()).unwrap();
+        assert_eq!((input.width, input.words[0]), (5, 0b00100));
+        assert_eq!(gamma_epsilon(&input), (22, 9));
+        let wide = vec!["1".repeat(64), "0".repeat(64)];
+        assert_eq!(gamma_epsilon(&parse_report(&wide).unwrap()), (u64::MAX, 0));
+        assert_eq!(parse_report(&["0".repeat(65)]), Err(ParseError::new(1, 1, &"0".repeat(65), "at most 64 bits")));
+        let ragged = vec!["101".to_owned(), "11".to_owned()];
+        assert_eq!(parse_report(&ragged), Err(ParseError::new(2, 1, "11", "3 bits")));
+    }
+
+    #[test]
+    fn wide_ratings() {
+        // Both ratings are at least 3 * 2^62, so their product is past i128::MAX
+        let wide = vec!["1".repeat(64), format!("11{}", "0".repeat(62))];
+        let rating = compute_life_support_rating(&parse_report(&wide).unwrap());
+        assert_eq!(rating, u64::MAX as u128 * (3 << 62));
+        assert!(rating > i128::MAX as u128);
+        let day = Day3::parse(&wide.join("\n")).unwrap();
+        assert_eq!(day.part2().to_string(), rating.to_string());
+    }
 }
@@ -0,0 +1,121 @@
+use std::fmt;
+
+use crate::Report;
+
+/// Which bit to follow at each position
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum Criterion {
+    MostCommon,
+    LeastCommon,
+}
+
+/// What to do when a position has as many 0s as 1s left
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum TieBreak {
+    PreferOne,
+    PreferZero,
+    Error,
+}
+
+/// Numbers sharing the bits above `bit` (the `prefix`) are split evenly on `bit`
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct Tie {
+    pub prefix: u64,
+    pub bit: u32,
+}
+
+impl fmt::Display for Tie {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "as many 0s as 1s at bit {} after prefix {:b}", self.bit, self.prefix)
+    }
+}
+
+impl std::error::Error for Tie {}
+
+#[derive(Debug, Clone, Default)]
+struct Node {
+    /// Numbers in the report that start with the path to this node
+    count: usize,
+    children: [Option<usize>; 2],
+}
+
+/// Binary trie over the report, most significant bit first
+#[derive(Debug, Clone)]
+pub struct Trie {
+    width: u32,
+    nodes: Vec<Node>,
+}
+
+impl Trie {
+    pub fn new(report: &Report) -> Self {
+        let mut nodes = vec![Node::default()];
+        for &word in report.words.iter() {
+            let mut node = 0;
+            nodes[0].count += 1;
+            for bit in (0..report.width).rev() {
+                let b = ((word >> bit) & 1) as usize;
+                node = match nodes[node].children[b] {
+                    Some(child) => child,
+                    None => {
+                        nodes.push(Node::default());
+                        nodes[node].children[b] = Some(nodes.len() - 1);
+                        nodes.len() - 1
+                    }
+                };
+                nodes[node].count += 1;
+            }
+        }
+        Trie { width: report.width, nodes }
+    }
+
+    /// Find a rating with one walk from the root to a leaf. Once only one
+    /// number is left below a node, the walk follows it to the end.
+    pub fn rating(&self, criterion: Criterion, tie: TieBreak) -> Result<u64, Tie> {
+        let mut node = &self.nodes[0];
+        let mut prefix = 0;
+        for bit in (0..self.width).rev() {
+            let count = |b: usize| node.children[b].map_or(0, |c| self.nodes[c].count);
+            let b = match (count(0), count(1)) {
+                (0, _) => 1,
+                (_, 0) => 0,
+                (zeros, ones) if zeros == ones => match tie {
+                    TieBreak::PreferOne => 1,
+                    TieBreak::PreferZero => 0,
+                    TieBreak::Error => return Err(Tie { prefix, bit }),
+                },
+                (zeros, ones) => match criterion {
+                    Criterion::MostCommon => (ones > zeros) as usize,
+                    Criterion::LeastCommon => (ones < zeros) as usize,
+                },
+            };
+            prefix = (prefix << 1) | b as u64;
+            node = &self.nodes[node.children[b].unwrap()];
+        }
+        Ok(prefix)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parse_report;
+    use aoc_common::read_lines;
+    #[test]
+    fn example() {
+        let trie = Trie::new(&parse_report(&read_lines("example").unwrap()).unwrap());
+        assert_eq!(trie.rating(Criterion::MostCommon, TieBreak::PreferOne), Ok(23));
+        assert_eq!(trie.rating(Criterion::LeastCommon, TieBreak::PreferZero), Ok(10));
+        assert_eq!(trie.rating(Criterion::MostCommon, TieBreak::PreferZero), Ok(22));
+        let tie = trie.rating(Criterion::MostCommon, TieBreak::Error).unwrap_err();
+        assert_eq!(tie, Tie { prefix: 0b1011, bit: 0 });
+        assert_eq!(tie.to_string(), "as many 0s as 1s at bit 0 after prefix 1011");
+    }
+
+    #[test]
+    fn duplicates() {
+        let input: Vec<String> = ["011", "011", "100"].iter().map(|s| s.to_string()).collect();
+        let trie = Trie::new(&parse_report(&input).unwrap());
+        assert_eq!(trie.rating(Criterion::MostCommon, TieBreak::Error), Ok(0b011));
+        assert_eq!(trie.rating(Criterion::LeastCommon, TieBreak::Error), Ok(0b100));
+    }
+}
@@ -1,109 +1,287 @@
-use std::io::{BufReader, Error, ErrorKind};
-use std::io::prelude::*;
-use std::fs::File;
-
-fn read_input(filename: &str) -> Result<Vec<String>, Error> {
-    let f = File::open(filename).unwrap();
-    let f = BufReader::new(f);
-    f.lines().map(|l| l.and_then(|v| v.parse().map_err(|e| Error::new(ErrorKind::InvalidData, e)))).collect()
-}
+use std::collections::HashMap;
+use aoc_common::{column, lines, parse_field, Answer, Error, Grid, ParseError, Pos, Solution};
+
+/// A bingo board, indexed by (row, column)
+pub type Board = Grid<usize>;
 
 /// Extract numbers read during bingo
-fn get_numbers(input: &Vec<String>) -> Vec<usize> {
-    input[0].split(",").map(|val| val.parse().unwrap()).collect()
+pub fn get_numbers(input: &[String]) -> Result<Vec<usize>, ParseError> {
+    let line = input.first().ok_or_else(|| ParseError::new(1, 1, "", "comma separated numbers"))?;
+    line.split(',').map(|val| parse_field(1, line, val.trim(), "a number")).collect()
 }
 
-/// Extract Bingo matrices
-/// Use Rust const generics to generalize methods on the size of the bingo matrix
-/// Although in this case, both parts operate on 5x5, it is a cool feature 
-fn get_boards<const T: usize>(input: &Vec<String>) -> Vec<[[usize; T]; T]> {
-    let mut boards: Vec<[[usize; T]; T]> = vec![];
-    for i in (2..input.len()).step_by(T+1) {
-        let mut board =  [[0; T]; T];
-        for j in i..i+T {
-            let tmp: Vec<&str> = input[j].trim().split_whitespace().collect();
-            for k in 0..T {
-                board[j - i][k] = tmp[k].parse().unwrap();
+/// Extract bingo boards from the lines after the numbers. Boards are
+/// separated by one or more blank lines and can be any size, as long as
+/// they all have as many rows and columns as the first one.
+pub fn get_boards(input: &[String]) -> Result<Vec<Board>, ParseError> {
+    // Indices of the lines making up each board
+    let mut groups: Vec<Vec<usize>> = vec![];
+    let mut in_board = false;
+    for (j, line) in input.iter().enumerate().skip(1) {
+        if line.trim().is_empty() {
+            in_board = false;
+            continue;
+        }
+        if !in_board {
+            groups.push(vec![]);
+            in_board = true;
+        }
+        groups.last_mut().unwrap().push(j);
+    }
+    let first = groups.first().ok_or_else(|| ParseError::new(input.len() + 1, 1, "", "a bingo board"))?;
+    let (height, width) = (first.len(), input[first[0]].split_whitespace().count());
+    let expected = format!("{} numbers", width);
+
+    let mut boards = vec![];
+    for group in groups.iter() {
+        let mut cells = Vec::with_capacity(width * height);
+        for (row, &j) in group.iter().enumerate() {
+            let line = &input[j];
+            if row == height {
+                return Err(ParseError::new(j + 1, 1, line, &format!("a blank line after {} rows", height)));
+            }
+            let tmp: Vec<&str> = line.split_whitespace().collect();
+            if tmp.len() != width {
+                let col = tmp.get(width).map_or(line.len() + 1, |extra| column(line, extra));
+                return Err(ParseError::new(j + 1, col, line, &expected));
             }
+            for val in tmp {
+                cells.push(parse_field(j + 1, line, val, "a number")?);
+            }
+        }
+        if group.len() < height {
+            let j = group[group.len() - 1] + 1;
+            return Err(ParseError::new(j + 1, 1, input.get(j).map_or("", |s| s.as_str()), "a board row"));
         }
-        boards.push(board);
+        boards.push(Grid::from_fn(width, height, |(i, k)| cells[i * width + k]));
     }
-    boards
+    Ok(boards)
 }
 
-/// Conduct simulation and compute winning score
-fn conduct_bingo<const T: usize>(numbers: &Vec<usize>, mut boards: Vec<[[usize; T]; T]>, break_early: bool) -> usize {
-    let mut winner = 0;
-    let mut winning_num = 0;
-    let mut last = 0;
-    let mut last_winning_num = 0;
-
-    // Wrap the bingo simulation in a closure that allows early return for part 1
-    let mut bingo_loop = || {
-        let mut row_done: Vec<[usize; T]> = vec![[T; T]; boards.len()];
-        let mut col_done = row_done.clone();
-        let mut board_done = vec![false; boards.len()];
-        for num in numbers.iter() {
-            for (ind, board) in boards.iter_mut().enumerate() {
-                for i in 0..T {
-                    for j in 0..T {
-                        if board[i][j] == *num {
-                            row_done[ind][i] -= 1;
-                            col_done[ind][j] -= 1;
-                            board[i][j] = usize::MAX;
-                            if row_done[ind][i] == 0 || col_done[ind][j] == 0 {
-                                board_done[ind] = true;
-                                if board_done.iter().all(|&val| val) {
-                                    last = ind;
-                                    last_winning_num = *num;
-                                    return;
-                                }
-                                winner = ind;
-                                winning_num = *num;
-                                if break_early {
-                                    return;
-                                }
-                            }
-                        }
-                    }
-                }
-            }
+/// A way of completing a board. A board wins as soon as any of the rules
+/// in play is met.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum WinRule {
+    Rows,
+    Columns,
+    /// Either diagonal, on square boards only
+    Diagonals,
+    FourCorners,
+    FullCard,
+}
+
+/// The rules of the puzzle
+pub const PUZZLE_RULES: [WinRule; 2] = [WinRule::Rows, WinRule::Columns];
+
+/// One board completing
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct Finish {
+    pub board: usize,
+    /// Index into the called numbers
+    pub call: usize,
+    pub number: usize,
+    /// Sum of the unmarked numbers on the board times `number`
+    pub score: usize,
+}
+
+/// Marks made so far on one board
+struct Progress {
+    rows: Vec<usize>,
+    cols: Vec<usize>,
+    diagonals: [usize; 2],
+    corners: usize,
+    marked: Grid<bool>,
+    total: usize,
+    unmarked_sum: usize,
+    finished: bool,
+}
+
+impl Progress {
+    fn new(board: &Board) -> Self {
+        Progress {
+            rows: vec![0; board.height()],
+            cols: vec![0; board.width()],
+            diagonals: [0; 2],
+            corners: 0,
+            marked: Grid::new(board.width(), board.height(), false),
+            total: 0,
+            unmarked_sum: board.iter().sum(),
+            finished: false,
+        }
+    }
+
+    /// Mark the number at `(i, j)` and check whether that completes the board
+    fn mark(&mut self, board: &Board, (i, j): Pos, rules: &[WinRule]) -> bool {
+        if self.marked[(i, j)] {
+            return false;
+        }
+        let (height, width) = (board.height(), board.width());
+        let last_row = height - 1;
+        let last_col = width - 1;
+        let square = height == width;
+        self.marked[(i, j)] = true;
+        self.total += 1;
+        self.unmarked_sum -= board[(i, j)];
+        self.rows[i] += 1;
+        self.cols[j] += 1;
+        if square && i == j {
+            self.diagonals[0] += 1;
+        }
+        if square && i + j == last_col {
+            self.diagonals[1] += 1;
+        }
+        if (i == 0 || i == last_row) && (j == 0 || j == last_col) {
+            self.corners += 1;
+        }
+        // Boards one cell wide or high have fewer than four distinct corners
+        let num_corners = (if height > 1 { 2 } else { 1 }) * (if width > 1 { 2 } else { 1 });
+        rules.iter().any(|rule| match rule {
+            WinRule::Rows => self.rows[i] == width,
+            WinRule::Columns => self.cols[j] == height,
+            WinRule::Diagonals => square && (self.diagonals[0] == width || self.diagonals[1] == width),
+            WinRule::FourCorners => self.corners == num_corners,
+            WinRule::FullCard => self.total == width * height,
+        })
+    }
+}
+
+/// Call every number in turn and return each board as it wins, in
+/// finishing order. Boards that never win are left out.
+pub fn play(numbers: &[usize], boards: &[Board], rules: &[WinRule]) -> Vec<Finish> {
+    // Where each number is on every board, so a call only visits the cells it marks
+    let mut index: HashMap<usize, Vec<(usize, Pos)>> = HashMap::new();
+    for (b, board) in boards.iter().enumerate() {
+        for pos in board.positions() {
+            index.entry(board[pos]).or_default().push((b, pos));
         }
-    };
-    bingo_loop();
-    if (!break_early) {
-        winner = last;
-        winning_num = last_winning_num;
     }
-    let mut unmarked_sum = 0;
-    for i in 0..T {
-        for j in 0..T {
-            if boards[winner][i][j] != usize::MAX {
-                unmarked_sum += boards[winner][i][j];
+    let mut progress: Vec<Progress> = boards.iter().map(Progress::new).collect();
+    let mut finishes = vec![];
+    for (call, &number) in numbers.iter().enumerate() {
+        for &(b, pos) in index.get(&number).map_or(&[][..], |v| v.as_slice()) {
+            let p = &mut progress[b];
+            if !p.finished && p.mark(&boards[b], pos, rules) {
+                p.finished = true;
+                finishes.push(Finish { board: b, call, number, score: p.unmarked_sum * number });
             }
         }
+        if finishes.len() == boards.len() {
+            break;
+        }
+    }
+    finishes
+}
+
+/// Numbers to call and the bingo boards
+pub struct Day4 {
+    numbers: Vec<usize>,
+    boards: Vec<Board>,
+}
+
+impl Day4 {
+    fn score(finish: Option<&Finish>) -> Answer {
+        match finish {
+            Some(finish) => finish.score.into(),
+            None => "no board wins".into(),
+        }
+    }
+}
+
+impl Solution for Day4 {
+    const DAY: u32 = 4;
+
+    fn parse(input: &str) -> Result<Self, Error> {
+        let input = lines(input);
+        Ok(Day4 { numbers: get_numbers(&input)?, boards: get_boards(&input)? })
+    }
+
+    fn part1(&self) -> Answer {
+        Day4::score(play(&self.numbers, &self.boards, &PUZZLE_RULES).first())
+    }
+
+    fn part2(&self) -> Answer {
+        Day4::score(play(&self.numbers, &self.boards, &PUZZLE_RULES).last())
     }
-    unmarked_sum * winning_num
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use aoc_common::read_lines;
     #[test]
     fn example() {
-        let input = read_input("example").unwrap();
-        let numbers = get_numbers(&input);
-        let boards = get_boards::<5>(&input);
-        println!("Part1: {}", conduct_bingo::<5>(&numbers, boards.clone(), true));
-        println!("Part2: {}", conduct_bingo::<5>(&numbers, boards.clone(), false));
+        let input = read_lines("example").unwrap();
+        let numbers = get_numbers(&input).unwrap();
+        let boards = get_boards(&input).unwrap();
+        let finishes = play(&numbers, &boards, &PUZZLE_RULES);
+        println!("Part1: {}", finishes[0].score);
+        println!("Part2: {}", finishes[finishes.len() - 1].score);
     }
 
     #[test]
     fn actual() {
-        let input = read_input("input").unwrap();
-        let numbers = get_numbers(&input);
-        let boards = get_boards::<5>(&input);
-        println!("Part1: {}", conduct_bingo::<5>(&numbers, boards.clone(), true));
-        println!("Part2: {}", conduct_bingo::<5>(&numbers, boards.clone(), false));
+        let input = read_lines("input").unwrap();
+        let numbers = get_numbers(&input).unwrap();
+        let boards = get_boards(&input).unwrap();
+        let finishes = play(&numbers, &boards, &PUZZLE_RULES);
+        println!("Part1: {}", finishes[0].score);
+        println!("Part2: {}", finishes[finishes.len() - 1].score);
+    }
+
+    #[test]
+    fn bad_input() {
+        let mut input = read_lines("example").unwrap();
+        input[3] = " 8  2 23  4".to_owned();
+        assert_eq!(get_boards(&input), Err(ParseError::new(4, 12, " 8  2 23  4", "5 numbers")));
+        input[3] = " 8  2 23  4 24".to_owned();
+        // Boards can be any size, but all the same as the first
+        input.truncate(11);
+        assert_eq!(get_boards(&input), Err(ParseError::new(12, 1, "", "a board row")));
+        assert_eq!(get_numbers(&["7,4,x".to_owned()]), Err(ParseError::new(1, 5, "x", "a number")));
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn board_shapes() {
+        let input: Vec<String> = ["7, 4,9 ,5", "", "", "1 2  3", " 4  5 6", "", "", "", "7 8 9", "10 11 12", ""]
+            .iter()
+            .map(|s| s.to_string())
+            .collect();
+        assert_eq!(get_numbers(&input).unwrap(), vec![7, 4, 9, 5]);
+        let boards = get_boards(&input).unwrap();
+        assert_eq!(boards.len(), 2);
+        assert_eq!((boards[1].height(), boards[1].width()), (2, 3));
+        assert_eq!(boards[1].row(1), &[10, 11, 12]);
+        // Columns of these boards are only two numbers long
+        let finish = play(&[7, 10], &boards, &PUZZLE_RULES)[0];
+        assert_eq!((finish.board, finish.score), (1, 10 * (8 + 9 + 11 + 12)));
+
+        let mut ragged = input.clone();
+        ragged[9] = "10 11".to_owned();
+        assert_eq!(get_boards(&ragged), Err(ParseError::new(10, 6, "10 11", "3 numbers")));
+        let mut tall = input.clone();
+        tall[10] = "13 14 15".to_owned();
+        assert_eq!(get_boards(&tall), Err(ParseError::new(11, 1, "13 14 15", "a blank line after 2 rows")));
+        assert_eq!(get_boards(&input[..2]), Err(ParseError::new(3, 1, "", "a bingo board")));
+    }
+
+    #[test]
+    fn win_rules() {
+        let input = read_lines("example").unwrap();
+        let numbers = get_numbers(&input).unwrap();
+        let boards = get_boards(&input).unwrap();
+        let finishes = play(&numbers, &boards, &PUZZLE_RULES);
+        let order: Vec<(usize, usize, usize)> = finishes.iter().map(|f| (f.board, f.number, f.score)).collect();
+        assert_eq!(order, vec![(2, 24, 4512), (0, 16, 2192), (1, 13, 1924)]);
+
+        // Board 0 diagonal: 22 2 14 18 19
+        let finishes = play(&[22, 2, 14, 18, 19], &boards, &[WinRule::Diagonals]);
+        assert_eq!(finishes.len(), 1);
+        assert_eq!((finishes[0].board, finishes[0].call), (0, 4));
+        let corners = play(&[22, 0, 1, 19], &boards, &[WinRule::FourCorners]);
+        assert_eq!(corners.iter().map(|f| f.board).collect::<Vec<usize>>(), vec![0]);
+        let full = play(&numbers, &boards, &[WinRule::FullCard]);
+        assert_eq!(full.len(), 3);
+        assert!(full.iter().all(|f| f.score == 0));
+        assert!(play(&numbers[..4], &boards, &PUZZLE_RULES).is_empty());
+    }
+}
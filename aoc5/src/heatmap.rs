@@ -0,0 +1,242 @@
+use std::fmt;
+use aoc_common::Grid;
+use crate::{points, Orientations, Segment};
+
+/// Colours for no coverage and then increasing overlap, blended in between
+const SCALE: [[u8; 3]; 5] = [[0, 0, 0], [0, 0, 255], [0, 255, 0], [255, 255, 0], [255, 0, 0]];
+
+/// Most cells a heatmap may have, about 128 MB of counts
+pub const MAX_CELLS: usize = 1 << 24;
+
+/// A heatmap would need more than `MAX_CELLS` cells at this bin size
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct TooLarge {
+    pub width: usize,
+    pub height: usize,
+    pub scale: usize,
+}
+
+impl fmt::Display for TooLarge {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "a {}x{} heatmap at scale {} is over {} cells, use a larger scale", self.width, self.height, self.scale, MAX_CELLS)
+    }
+}
+
+impl std::error::Error for TooLarge {}
+
+/// Coverage of the segments' bounding box, indexed `(y, x)` so rows run down
+/// the map like in the puzzle. Each cell is a `scale` by `scale` square of
+/// points holding the number of segments passing through it.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Heatmap {
+    counts: Grid<usize>,
+    /// Point `(x, y)` at the top left of the map
+    origin: (usize, usize),
+    scale: usize,
+}
+
+impl Heatmap {
+    /// One cell per point
+    pub fn new(input: &[Segment], orientations: Orientations) -> Result<Self, TooLarge> {
+        Heatmap::binned(input, orientations, 1)
+    }
+
+    /// One cell per `scale` by `scale` square of points
+    pub fn binned(input: &[Segment], orientations: Orientations, scale: usize) -> Result<Self, TooLarge> {
+        assert!(scale >= 1, "scale must be at least 1");
+        let segments: Vec<&Segment> = input.iter().filter(|s| orientations.includes(s)).collect();
+        let ((x0, y0), (x1, y1)) = bounds(&segments);
+        let (width, height) = match segments.is_empty() {
+            true => (0, 0),
+            false => ((x1 - x0) / scale + 1, (y1 - y0) / scale + 1),
+        };
+        if width.checked_mul(height).is_none_or(|cells| cells > MAX_CELLS) {
+            return Err(TooLarge { width, height, scale });
+        }
+        // Count each bin one segment at a time, so a segment crossing a bin
+        // on several points still counts once there
+        let mut counts = Grid::new(width, height, 0);
+        let mut covered = Grid::new(width, height, false);
+        for segment in segments {
+            let cells: Vec<(usize, usize)> = points(segment)
+                .map(|(x, y)| ((y - y0) / scale, (x - x0) / scale))
+                .filter(|&pos| !std::mem::replace(&mut covered[pos], true))
+                .collect();
+            for pos in cells {
+                covered[pos] = false;
+                counts[pos] += 1;
+            }
+        }
+        Ok(Heatmap { counts, origin: (x0, y0), scale })
+    }
+
+    /// The finest map no more than `max_side` cells wide or high
+    pub fn fitted(input: &[Segment], orientations: Orientations, max_side: usize) -> Result<Self, TooLarge> {
+        assert!(max_side >= 1, "a map needs at least one cell per side");
+        let segments: Vec<&Segment> = input.iter().filter(|s| orientations.includes(s)).collect();
+        let ((x0, y0), (x1, y1)) = bounds(&segments);
+        let span = (x1 - x0).max(y1 - y0) + 1;
+        Heatmap::binned(input, orientations, span.div_ceil(max_side).max(1))
+    }
+
+    pub fn counts(&self) -> &Grid<usize> {
+        &self.counts
+    }
+
+    pub fn origin(&self) -> (usize, usize) {
+        self.origin
+    }
+
+    pub fn scale(&self) -> usize {
+        self.scale
+    }
+
+    /// Largest number of segments covering one cell
+    pub fn max(&self) -> usize {
+        self.counts.iter().copied().max().unwrap_or(0)
+    }
+
+    /// Number of cells covered by at least two segments, which at scale 1
+    /// are the points where vents overlap
+    pub fn overlaps(&self) -> usize {
+        self.counts.iter().filter(|&&n| n > 1).count()
+    }
+
+    /// The map as drawn in the puzzle: `.` for no segment, otherwise the
+    /// number of segments, with `#` for more than 9
+    pub fn to_ascii(&self) -> String {
+        let mut res = String::with_capacity((self.counts.width() + 1) * self.counts.height());
+        for row in self.counts.rows() {
+            res.extend(row.iter().map(|&n| match n {
+                0 => '.',
+                1..=9 => char::from_digit(n as u32, 10).unwrap(),
+                _ => '#',
+            }));
+            res.push('\n');
+        }
+        res
+    }
+
+    /// Binary PGM image, brighter where more segments overlap
+    pub fn to_pgm(&self) -> Vec<u8> {
+        let max = self.max().max(1);
+        let mut res = format!("P5\n{} {}\n255\n", self.counts.width(), self.counts.height()).into_bytes();
+        res.extend(self.counts.iter().map(|&n| (n * 255 / max) as u8));
+        res
+    }
+
+    /// Binary PPM image, running from black for no segment through blue,
+    /// green and yellow to red for the most overlapped points
+    pub fn to_ppm(&self) -> Vec<u8> {
+        let max = self.max();
+        let mut res = format!("P6\n{} {}\n255\n", self.counts.width(), self.counts.height()).into_bytes();
+        res.extend(self.counts.iter().flat_map(|&n| color(n, max)));
+        res
+    }
+}
+
+/// Smallest and largest corners of the segments, the origin if there are none
+fn bounds(segments: &[&Segment]) -> ((usize, usize), (usize, usize)) {
+    let xs = || segments.iter().flat_map(|s| [s.0.0, s.1.0]);
+    let ys = || segments.iter().flat_map(|s| [s.0.1, s.1.1]);
+    (
+        (xs().min().unwrap_or(0), ys().min().unwrap_or(0)),
+        (xs().max().unwrap_or(0), ys().max().unwrap_or(0)),
+    )
+}
+
+/// Colour for `count` on a scale reaching red at `max`
+pub fn color(count: usize, max: usize) -> [u8; 3] {
+    if count == 0 || max == 0 {
+        return SCALE[0];
+    }
+    // Counts 1..=max spread evenly over the coloured stops, as the stop
+    // below and how far it is towards the next one, out of `span`
+    let stops = SCALE.len() - 2;
+    let span = (max - 1).max(1);
+    let t = (count.min(max) - 1) * stops;
+    let (k, rem) = (t / span, t % span);
+    if k >= stops {
+        return SCALE[stops + 1];
+    }
+    let (a, b) = (SCALE[k + 1], SCALE[k + 2]);
+    [0, 1, 2].map(|c| ((a[c] as usize * (span - rem) + b[c] as usize * rem) / span) as u8)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{count_overlaps, parse_segments};
+    use aoc_common::read_lines;
+    #[test]
+    fn example() {
+        let input = parse_segments(read_lines("example").unwrap()).unwrap();
+        let map = Heatmap::new(&input, Orientations::AxisAligned).unwrap();
+        assert_eq!(map.to_ascii(), "\
+.......1..
+..1....1..
+..1....1..
+.......1..
+.112111211
+..........
+..........
+..........
+..........
+222111....
+");
+        let map = Heatmap::new(&input, Orientations::All).unwrap();
+        assert_eq!(map.to_ascii(), "\
+1.1....11.
+.111...2..
+..2.1.111.
+...1.2.2..
+.112313211
+...1.2....
+..1...1...
+.1.....1..
+1.......1.
+222111....
+");
+        assert_eq!(map.overlaps(), count_overlaps(&input, Orientations::All));
+        assert_eq!(map.max(), 3);
+    }
+
+    #[test]
+    fn images() {
+        let map = Heatmap::new(&[((0, 0), (2, 0)), ((1, 0), (1, 1))], Orientations::All).unwrap();
+        assert_eq!(map.to_pgm(), b"P5\n3 2\n255\n\x7f\xff\x7f\x00\x7f\x00".to_vec());
+        let ppm = map.to_ppm();
+        assert!(ppm.starts_with(b"P6\n3 2\n255\n"));
+        assert_eq!(ppm.len(), 11 + 3 * 6);
+        assert_eq!(&ppm[11..17], &[0, 0, 255, 255, 0, 0]);
+        assert_eq!(&ppm[20..23], &[0, 0, 0]);
+
+        assert_eq!(color(0, 10), [0, 0, 0]);
+        assert_eq!(color(1, 10), [0, 0, 255]);
+        assert_eq!(color(10, 10), [255, 0, 0]);
+        assert_eq!(color(7, 10), [255, 255, 0]);
+        assert_eq!(color(4, 2), [255, 0, 0]);
+        assert_eq!(color(2, 3), [127, 255, 0]);
+    }
+
+    #[test]
+    fn large_coordinates() {
+        let segments = [
+            ((1_000_000, 1_000_000), (3_000_000, 3_000_000)),
+            ((1_000_000, 3_000_000), (3_000_000, 1_000_000)),
+            ((2_000_000, 1_000_000), (2_000_000, 3_000_000)),
+        ];
+        let err = Heatmap::new(&segments, Orientations::All).unwrap_err();
+        assert_eq!(err, TooLarge { width: 2_000_001, height: 2_000_001, scale: 1 });
+        let map = Heatmap::fitted(&segments, Orientations::All, 100).unwrap();
+        assert_eq!((map.origin(), map.scale()), ((1_000_000, 1_000_000), 20_001));
+        assert_eq!((map.counts().width(), map.counts().height()), (100, 100));
+        // All three lines meet in the middle bin
+        assert_eq!(map.max(), 3);
+        assert_eq!(map.counts()[(49, 49)], 3);
+
+        // Only the bounding box is mapped
+        let map = Heatmap::new(&[((500, 7), (503, 7)), ((501, 6), (501, 8))], Orientations::All).unwrap();
+        assert_eq!((map.origin(), map.to_ascii()), ((500, 6), ".1..\n1211\n.1..\n".to_owned()));
+    }
+}
@@ -1,123 +1,194 @@
-use std::io::{BufReader, Error, ErrorKind};
-use std::io::prelude::*;
-use std::fs::File;
-use std::cmp::{min, max};
-use std::collections::{HashSet, HashMap};
-
-fn read_input(filename: &str) -> Result<Vec<String>, Error> {
-    let f = File::open(filename).unwrap();
-    let f = BufReader::new(f);
-    f.lines().map(|l| l.and_then(|v| v.parse().map_err(|e| Error::new(ErrorKind::InvalidData, e)))).collect()
+use std::collections::HashMap;
+use aoc_common::{lines, parse_field, split_field, Answer, Error, ParseError, Solution};
+
+mod heatmap;
+mod overlap;
+pub use heatmap::{color, Heatmap, TooLarge, MAX_CELLS};
+pub use overlap::count_overlaps;
+
+pub type Segment = ((usize, usize), (usize, usize));
+
+/// Which segments take part when counting overlaps
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum Orientations {
+    /// Horizontal and vertical segments
+    AxisAligned,
+    /// Segments at 45°
+    Diagonal,
+    /// Every segment, whatever its slope
+    All,
 }
 
-type Segment= ((usize, usize), (usize, usize));
-
-/// Consider all horizontal/vertical line segments and compute number of points of intersection
-fn hv_intersect(input: &Vec<Segment>, diagonal: bool) -> usize {
-    let mut points: HashSet<(usize, usize)> = HashSet::new(); // Intersecting points
-    for i in 0..input.len() {
-        for j in i+1..input.len() {
-            //println!("Segment1 {:?}, Segment2 {:?} res: {}", input[i], input[j], res);
-            let (x1, y1) = input[i].0;
-            let (x2, y2) = input[i].1;
-
-            let (x3, y3) = input[j].0;
-            let (x4, y4) = input[j].1;
-
-            // Both Vertical
-            if (x1 == x2 && x3 == x4 && x1 == x3 && max(y1, y3) <= min(y2, y4)) {
-                //println!("X equal Lines {:?}, {:?}", input[i], input[j]);
-                for k in max(y1, y3)..(min(y2, y4)+1) {
-                    points.insert((x1, k));
-                }
-            }
-            // Both Horizontal
-            if (y1 == y2 && y3 == y4 && y1 == y3 && max(x1, x3) <= min(x2, x4)) {
-                //println!("Y equal Lines {:?}, {:?}", input[i], input[j]);
-                for k in max(x1, x3)..(min(x2, x4)+1) {
-                    points.insert((k, y1));
-                }
-            }
-            // If one Horizontal and One vertical - x1 == x2 && y3 == y4 || x3 == x4 && y1 == y2 
-            if (x1 == x2 && y3 == y4 && y3 >= min(y1, y2) && y3 <= max(y1, y2) && x1 >= min(x3, x4) && x1 <= max(x3, x4)) {
-                //println!("Perpendicular 1 Lines {:?}, {:?}", input[i], input[j]);
-                points.insert((x1, y3));
-            }
-            if (x3 == x4 && y1 == y2 && y1 >= min(y3, y4) && y1 <= max(y3, y4) && x3 >= min(x1, x2) && x3 <= max(x1, x2)) {
-                //println!(" Perpendicular 2 Lines {:?}, {:?}", input[i], input[j]);
-                points.insert((x3, y1));
-            }
+impl Orientations {
+    pub fn includes(self, &((x1, y1), (x2, y2)): &Segment) -> bool {
+        match self {
+            Orientations::AxisAligned => x1 == x2 || y1 == y2,
+            Orientations::Diagonal => x1 != x2 && x1.abs_diff(x2) == y1.abs_diff(y2),
+            Orientations::All => true,
         }
     }
-    points.len()
 }
 
-fn dumb_compute(input: &Vec<Segment>) -> usize {
-    let mut points: HashMap<(usize, usize), usize> = HashMap::new(); // Intersecting points
-    for i in 0..input.len() {
-        let (x1, y1) = input[i].0;
-        let (x2, y2) = input[i].1;
-
-        if x1 == x2 {
-            let range = match y1 < y2 {
-                true => y1..y2+1,
-                false => y2..y1+1,
-            };
-            for y in range {
-                let ch = points.entry((x1, y)).or_insert(0);
-                *ch += 1;
-            }
-            continue;
-        }
-        if y1 == y2 {
-            for x in x1..x2+1 {
-                let ch = points.entry((x, y1)).or_insert(0);
-                *ch += 1;
-            }
-            continue;
-        }
+fn gcd(a: u64, b: u64) -> u64 {
+    match b {
+        0 => a,
+        _ => gcd(b, a % b),
+    }
+}
 
-        let m1 = (y2 as i32 - y1 as i32) / (x2 as i32 - x1 as i32);
-        let c1 = y1 as i32 - m1 * (x1 as i32);
+/// Every lattice point on the segment, from the first endpoint to the second.
+/// Steps are the offset between the endpoints divided by the gcd of its
+/// components, so no point is skipped or rounded whatever the slope.
+pub fn points(&((x1, y1), (x2, y2)): &Segment) -> impl Iterator<Item = (usize, usize)> {
+    let (dx, dy) = (x2 as i64 - x1 as i64, y2 as i64 - y1 as i64);
+    let steps = gcd(dx.unsigned_abs(), dy.unsigned_abs()) as i64;
+    let (sx, sy) = match steps {
+        0 => (0, 0),
+        _ => (dx / steps, dy / steps),
+    };
+    (0..=steps).map(move |k| ((x1 as i64 + k * sx) as usize, (y1 as i64 + k * sy) as usize))
+}
 
-        for x in x1..x2+1 {
-            let y = (m1 * x as i32 + c1) as usize;
-            let ch = points.entry((x, y)).or_insert(0);
-            *ch += 1;
+/// Count overlaps by marking every point of every segment
+pub fn dumb_compute(input: &[Segment], orientations: Orientations) -> usize {
+    let mut marks: HashMap<(usize, usize), usize> = HashMap::new(); // Segments covering each point
+    for segment in input.iter().filter(|s| orientations.includes(s)) {
+        for p in points(segment) {
+            *marks.entry(p).or_insert(0) += 1;
         }
     }
-    points.retain(|_, v| *v > 1);
-    points.iter().filter(|&(k, v)| *v > 1).count()
+    marks.values().filter(|&v| *v > 1).count()
 }
 
-fn parse_segments(input: Vec<String>) -> Vec<Segment> {
+pub fn parse_segments(input: Vec<String>) -> Result<Vec<Segment>, ParseError> {
     input.iter()
-        .map(|val|
-            val.split("->").collect())
-        .map(|ss: Vec<&str>| (ss[0].trim().split(",").collect(), ss[1].trim().split(",").collect()))
-        .map(|(s0, s1): (Vec<&str>, Vec<&str>)| {
-            let mut v = vec![(s0[0].parse().unwrap(), s0[1].parse().unwrap()), (s1[0].parse().unwrap(), s1[1].parse().unwrap())];
+        .enumerate()
+        .map(|(i, val)| {
+            let point = |s: &str| -> Result<(usize, usize), ParseError> {
+                let (x, y) = split_field(i + 1, val, s.trim(), ",", "x,y")?;
+                Ok((parse_field(i + 1, val, x, "a number")?, parse_field(i + 1, val, y, "a number")?))
+            };
+            let (s0, s1) = split_field(i + 1, val, val, "->", "x1,y1 -> x2,y2")?;
+            let mut v = [point(s0)?, point(s1)?];
             v.sort();
-            v
+            Ok((v[0], v[1]))
         })
-        .map(|s: Vec<(usize, usize)>| (s[0], s[1]))
         .collect()
 }
 
+/// Hydrothermal vent lines
+pub struct Day5 {
+    segments: Vec<Segment>,
+}
+
+impl Day5 {
+    /// Coverage map of the segments in `orientations`
+    pub fn heatmap(&self, orientations: Orientations, scale: usize) -> Result<Heatmap, TooLarge> {
+        Heatmap::binned(&self.segments, orientations, scale)
+    }
+}
+
+impl Solution for Day5 {
+    const DAY: u32 = 5;
+
+    fn parse(input: &str) -> Result<Self, Error> {
+        Ok(Day5 { segments: parse_segments(lines(input))? })
+    }
+
+    fn part1(&self) -> Answer {
+        count_overlaps(&self.segments, Orientations::AxisAligned).into()
+    }
+
+    fn part2(&self) -> Answer {
+        count_overlaps(&self.segments, Orientations::All).into()
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use aoc_common::{read_lines, Rng};
     #[test]
     fn example() {
-        let input = parse_segments(read_input("example").unwrap());
-        println!("Part1: {}", hv_intersect(&input, false));
-        println!("Part2: {}", dumb_compute(&input));
+        let input = parse_segments(read_lines("example").unwrap()).unwrap();
+        println!("Part1: {}", count_overlaps(&input, Orientations::AxisAligned));
+        println!("Part2: {}", count_overlaps(&input, Orientations::All));
     }
 
     #[test]
     fn actual() {
-        let input = parse_segments(read_input("input").unwrap());
-        println!("Part1: {}", hv_intersect(&input, false));
-        println!("Part2: {}", dumb_compute(&input));
+        let input = parse_segments(read_lines("input").unwrap()).unwrap();
+        println!("Part1: {}", count_overlaps(&input, Orientations::AxisAligned));
+        println!("Part2: {}", count_overlaps(&input, Orientations::All));
+    }
+
+    #[test]
+    fn bad_input() {
+        let input = vec!["0,9 -> 5,9".to_owned(), "8,0 -> 0;8".to_owned()];
+        assert_eq!(parse_segments(input), Err(ParseError::new(2, 8, "0;8", "x,y")));
+        let input = vec!["0,9 -> 5,9".to_owned(), "8,0 -> 0,".to_owned()];
+        assert_eq!(parse_segments(input), Err(ParseError::new(2, 10, "", "a number")));
+        let input = vec!["0,9 5,9".to_owned()];
+        assert_eq!(parse_segments(input), Err(ParseError::new(1, 1, "0,9 5,9", "x1,y1 -> x2,y2")));
+    }
+
+    /// Horizontal and vertical segments, and 45° ones when `diagonal` is set,
+    /// with endpoints ordered like `parse_segments` does
+    fn random_segments(rng: &mut Rng, diagonal: bool) -> Vec<Segment> {
+        (0..rng.below(12) + 1)
+            .map(|_| {
+                let fixed = rng.below(15);
+                let mut span = [rng.below(15), rng.below(15)];
+                span.sort();
+                let (p, q) = if diagonal && rng.flip() {
+                    let len = span[1] - span[0];
+                    match rng.flip() {
+                        true => ((span[0], fixed), (span[1], fixed + len)),
+                        false => ((span[0], fixed + len), (span[1], fixed)),
+                    }
+                } else {
+                    match rng.flip() {
+                        true => ((fixed, span[0]), (fixed, span[1])),
+                        false => ((span[0], fixed), (span[1], fixed)),
+                    }
+                };
+                // Either endpoint first, since callers need not order them like parse_segments
+                if rng.flip() { (p, q) } else { (q, p) }
+            })
+            .collect()
     }
-}
\ No newline at end of file
+
+    /// Segments between any two points, at whatever slope
+    fn random_sloped(rng: &mut Rng) -> Vec<Segment> {
+        (0..rng.below(4))
+            .map(|_| ((rng.below(15), rng.below(15)), (rng.below(15), rng.below(15))))
+            .collect()
+    }
+
+    #[test]
+    fn count_overlaps_matches_dumb_compute() {
+        let mut rng = Rng::new(5);
+        for _ in 0..5000 {
+            let segments = random_segments(&mut rng, false);
+            assert_eq!(count_overlaps(&segments, Orientations::AxisAligned), dumb_compute(&segments, Orientations::All), "{:?}", segments);
+            let mut segments = random_segments(&mut rng, true);
+            assert_eq!(count_overlaps(&segments, Orientations::All), dumb_compute(&segments, Orientations::All), "{:?}", segments);
+            segments.extend(random_sloped(&mut rng));
+            for orientations in [Orientations::AxisAligned, Orientations::Diagonal, Orientations::All] {
+                assert_eq!(count_overlaps(&segments, orientations), dumb_compute(&segments, orientations), "{:?} {:?}", orientations, segments);
+            }
+        }
+    }
+
+    #[test]
+    fn sloped_points() {
+        assert_eq!(points(&((0, 0), (4, 2))).collect::<Vec<_>>(), vec![(0, 0), (2, 1), (4, 2)]);
+        assert_eq!(points(&((1, 7), (7, 3))).collect::<Vec<_>>(), vec![(1, 7), (4, 5), (7, 3)]);
+        assert_eq!(points(&((2, 0), (3, 5))).count(), 2);
+        assert_eq!(points(&((4, 4), (4, 4))).collect::<Vec<_>>(), vec![(4, 4)]);
+        // Each pair meets at a different point: (2, 1), (0, 0) and (1, 2)
+        let segments = [((0, 0), (4, 2)), ((0, 3), (3, 0)), ((0, 0), (2, 4))];
+        assert_eq!(count_overlaps(&segments, Orientations::All), 3);
+        assert_eq!(count_overlaps(&segments, Orientations::Diagonal), 0);
+    }
+}
@@ -0,0 +1,241 @@
+use std::collections::{BTreeMap, HashMap};
+use crate::{points, Orientations, Segment};
+
+/// Direction of a segment. Every segment lies on a line `a * x + b * y = key`
+/// of its family and covers the positions `start..=end` along that line.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+enum Family {
+    Horizontal,
+    Vertical,
+    /// 45°, with y growing as x grows
+    Rising,
+    /// 45°, with y shrinking as x grows
+    Falling,
+}
+
+const FAMILIES: [Family; 4] = [Family::Horizontal, Family::Vertical, Family::Rising, Family::Falling];
+
+impl Family {
+    /// `None` for segments at any other slope
+    fn of(&((x1, y1), (x2, y2)): &Segment) -> Option<Family> {
+        if y1 == y2 {
+            Some(Family::Horizontal)
+        } else if x1 == x2 {
+            Some(Family::Vertical)
+        } else if x1.abs_diff(x2) == y1.abs_diff(y2) {
+            // Either endpoint may come first
+            Some(if (x1 < x2) == (y1 < y2) { Family::Rising } else { Family::Falling })
+        } else {
+            None
+        }
+    }
+
+    /// `(a, b)` in the line equation
+    fn coefficients(self) -> (i64, i64) {
+        match self {
+            Family::Horizontal => (0, 1),
+            Family::Vertical => (1, 0),
+            Family::Rising => (1, -1),
+            Family::Falling => (1, 1),
+        }
+    }
+
+    fn key(self, (x, y): (i64, i64)) -> i64 {
+        let (a, b) = self.coefficients();
+        a * x + b * y
+    }
+
+    fn pos(self, (x, y): (i64, i64)) -> i64 {
+        match self {
+            Family::Vertical => y,
+            _ => x,
+        }
+    }
+
+    fn point(self, key: i64, pos: i64) -> (i64, i64) {
+        match self {
+            Family::Horizontal => (pos, key),
+            Family::Vertical => (key, pos),
+            Family::Rising => (pos, pos - key),
+            Family::Falling => (pos, key - pos),
+        }
+    }
+
+    /// The lattice point where line `k1` of this family meets line `k2` of
+    /// `other`, if there is one
+    fn crossing(self, k1: i64, other: Family, k2: i64) -> Option<(i64, i64)> {
+        let (a1, b1) = self.coefficients();
+        let (a2, b2) = other.coefficients();
+        let det = a1 * b2 - a2 * b1;
+        let (x, y) = (k1 * b2 - k2 * b1, a1 * k2 - a2 * k1);
+        match det != 0 && x % det == 0 && y % det == 0 {
+            true => Some((x / det, y / det)),
+            false => None,
+        }
+    }
+}
+
+/// Part of a line covered by one segment, or by several when `multiple`
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+struct Span {
+    start: i64,
+    end: i64,
+    multiple: bool,
+}
+
+/// Covered spans of every line of one family, sorted and disjoint
+type Lines = BTreeMap<i64, Vec<Span>>;
+
+/// Sweep the intervals on one line, keeping how many of them cover each stretch
+fn spans(intervals: Vec<(i64, i64)>) -> Vec<Span> {
+    let mut events: Vec<(i64, i32)> = intervals.into_iter().flat_map(|(s, e)| [(s, 1), (e + 1, -1)]).collect();
+    events.sort_unstable();
+    let mut res: Vec<Span> = vec![];
+    let mut depth = 0;
+    for (k, &(pos, change)) in events.iter().enumerate() {
+        depth += change;
+        // Apply every event at the same position before emitting a span
+        let next = match events.get(k + 1) {
+            Some(&(next, _)) if next == pos => continue,
+            Some(&(next, _)) => next,
+            None => break,
+        };
+        if depth == 0 {
+            continue;
+        }
+        let multiple = depth > 1;
+        match res.last_mut() {
+            Some(last) if last.end + 1 == pos && last.multiple == multiple => last.end = next - 1,
+            _ => res.push(Span { start: pos, end: next - 1, multiple }),
+        }
+    }
+    res
+}
+
+/// How many segments of the family cover `pos` on the line: 0, 1, or 2 for several
+fn level(lines: &Lines, key: i64, pos: i64) -> u8 {
+    let spans = match lines.get(&key) {
+        Some(spans) => spans,
+        None => return 0,
+    };
+    let k = spans.partition_point(|s| s.end < pos);
+    match spans.get(k) {
+        Some(s) if s.start <= pos => if s.multiple { 2 } else { 1 },
+        _ => 0,
+    }
+}
+
+/// Number of points covered by at least two of the segments in `orientations`.
+///
+/// Overlaps along a line are found by sweeping the intervals on it, and
+/// overlaps between lines of different directions by visiting the lines
+/// that cross each span. Nothing is stored per point, so memory stays
+/// proportional to the number of segments however large the coordinates
+/// are. Only segments at other slopes than horizontal, vertical and 45°
+/// have their points enumerated.
+pub fn count_overlaps(input: &[Segment], orientations: Orientations) -> usize {
+    let mut intervals: Vec<BTreeMap<i64, Vec<(i64, i64)>>> = vec![BTreeMap::new(); FAMILIES.len()];
+    let mut sloped: HashMap<(i64, i64), usize> = HashMap::new();
+    for segment in input.iter().filter(|s| orientations.includes(s)) {
+        let family = match Family::of(segment) {
+            Some(family) => family,
+            None => {
+                for (x, y) in points(segment) {
+                    *sloped.entry((x as i64, y as i64)).or_insert(0) += 1;
+                }
+                continue;
+            }
+        };
+        let (p, q) = segment;
+        let (p, q) = ((p.0 as i64, p.1 as i64), (q.0 as i64, q.1 as i64));
+        let (s, e) = (family.pos(p), family.pos(q));
+        intervals[family as usize].entry(family.key(p)).or_default().push((s.min(e), s.max(e)));
+    }
+    let families: Vec<Lines> = intervals.into_iter()
+        .map(|lines| lines.into_iter().map(|(key, v)| (key, spans(v))).collect())
+        .collect();
+
+    // Points covered more than once along a single line
+    let mut res: usize = families.iter()
+        .flat_map(|lines| lines.values().flatten())
+        .filter(|s| s.multiple)
+        .map(|s| (s.end - s.start + 1) as usize)
+        .sum();
+
+    // Where lines of two directions cross, a point covered once by each
+    // is counted at the first two directions that cover it, and a point
+    // already counted in several directions above is taken off again
+    for (f, &first) in FAMILIES.iter().enumerate() {
+        for (g, &second) in FAMILIES.iter().enumerate().skip(f + 1) {
+            for (&key, spans) in families[f].iter() {
+                for span in spans.iter() {
+                    let a = second.key(first.point(key, span.start));
+                    let b = second.key(first.point(key, span.end));
+                    for &other in families[g].range(a.min(b)..=a.max(b)).map(|(k, _)| k) {
+                        let p = match first.crossing(key, second, other) {
+                            Some(p) => p,
+                            None => continue,
+                        };
+                        let levels = FAMILIES.map(|h| level(&families[h as usize], h.key(p), h.pos(p)));
+                        let mut others = levels.iter().enumerate().filter(|&(h, _)| h != f && h != g);
+                        let first_pair = others.all(|(h, &l)| l == 0 || (h > g && l == 1));
+                        let first_multiple = !levels[..f].contains(&2);
+                        match (levels[f], levels[g]) {
+                            (1, 1) if first_pair => res += 1,
+                            (2, 2) if first_multiple => res -= 1,
+                            _ => (),
+                        }
+                    }
+                }
+            }
+        }
+    }
+
+    // Points on the other segments, which were counted above if the lines
+    // already covered them twice
+    for (&p, &n) in sloped.iter() {
+        let covered: usize = FAMILIES.iter().map(|&h| level(&families[h as usize], h.key(p), h.pos(p)) as usize).sum();
+        if covered < 2 && covered + n >= 2 {
+            res += 1;
+        }
+    }
+    res
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    #[test]
+    fn large_coordinates() {
+        let segments = [
+            ((0, 0), (3_000_000, 3_000_000)),
+            ((0, 3_000_000), (3_000_000, 0)),
+            ((1_000_000, 0), (1_000_000, 3_000_000)),
+            ((0, 5), (2_000_000, 5)),
+            ((1_000_000, 5), (3_000_000, 5)),
+        ];
+        // The shared stretch of the horizontals, plus the crossings at (1.5M, 1.5M),
+        // (1M, 1M), (1M, 2M), (5, 5) and (2999995, 5)
+        assert_eq!(count_overlaps(&segments, Orientations::All), 1_000_001 + 5);
+        assert_eq!(count_overlaps(&segments, Orientations::AxisAligned), 1_000_001);
+    }
+
+    #[test]
+    fn three_way_crossing() {
+        // Horizontal, vertical and both diagonals all meet at (2, 2) only
+        let segments = [((0, 2), (4, 2)), ((2, 0), (2, 4)), ((0, 0), (4, 4)), ((0, 4), (4, 0))];
+        assert_eq!(count_overlaps(&segments, Orientations::All), 1);
+        assert_eq!(count_overlaps(&segments, Orientations::AxisAligned), 1);
+        // Diagonals of different parity never meet on a lattice point
+        assert_eq!(count_overlaps(&[((0, 0), (3, 3)), ((0, 3), (3, 0))], Orientations::All), 0);
+    }
+
+    #[test]
+    fn reversed_endpoints() {
+        // Diagonals given right to left still meet the horizontal at their ends
+        assert_eq!(count_overlaps(&[((3, 3), (0, 0)), ((0, 0), (3, 0))], Orientations::All), 1);
+        assert_eq!(count_overlaps(&[((3, 0), (0, 3)), ((0, 3), (3, 3))], Orientations::All), 1);
+        assert_eq!(Family::of(&((3, 3), (0, 0))), Some(Family::Rising));
+        assert_eq!(Family::of(&((3, 0), (0, 3))), Some(Family::Falling));
+    }
+}
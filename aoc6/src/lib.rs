@@ -1,43 +1,83 @@
-use std::collections::HashMap;
-
-fn simulate(input: &[u32], num_iterations: usize) -> usize {
-    let mut map: HashMap<u32, usize> = HashMap::new();
-    for val in input {
-        *map.entry(*val).or_insert(0) += 1;
-    }
-    for i in 0..num_iterations {
-        let mut birth_count = 0;
-        let mut tmp_map = HashMap::new();
-        for (k, v) in map.iter() {
-            match *k == 0 {
-                true => {
-                    *tmp_map.entry(6).or_insert(0) += v;
-                    *tmp_map.entry(8).or_insert(0) += v;
-                },
-                false => {
-                    *tmp_map.entry(k - 1).or_insert(0) += v;
-                }
-            }
-        }
-        map = tmp_map;
-    }
-    map.values().sum()
+use aoc_common::{column, lines, parse_field, Answer, Error, ParseError, Solution};
+
+mod lifecycle;
+mod matrix;
+mod natural;
+pub use lifecycle::{population_history, Lifecycle, Population, PopulationError, CAPACITY, LANTERNFISH};
+pub use matrix::{population, population_exact, population_mod, power, transition, Count, Modular};
+pub use natural::Natural;
+
+/// Number of lanternfish after `num_iterations` days
+pub fn simulate(input: &[u32], num_iterations: usize) -> Result<usize, PopulationError> {
+    let mut population = Population::new(LANTERNFISH, input)?;
+    for _ in 0..num_iterations {
+        population.step();
+    }
+    Ok(population.total())
+}
+
+/// Parse the comma separated list of timers on the first line
+pub fn parse_input(input: &[String]) -> Result<Vec<u32>, ParseError> {
+    let line = input.first().ok_or_else(|| ParseError::new(1, 1, "", "comma separated timers"))?;
+    line.trim()
+        .split(',')
+        .map(|v| match parse_field(1, line, v, "a timer")? {
+            t if LANTERNFISH.age(t as usize).is_some() => Ok(t),
+            _ => Err(ParseError::new(1, column(line, v), v, "a timer from 0 to 8")),
+        })
+        .collect()
+}
+
+/// Internal timers of the initial lanternfish
+pub struct Day6 {
+    timers: Vec<u32>,
+}
+
+impl Day6 {
+    /// Total population each day up to `days` under another lifecycle
+    pub fn history(&self, days: usize, lifecycle: Lifecycle) -> Result<Vec<usize>, PopulationError> {
+        population_history(&self.timers, days, lifecycle)
+    }
+}
+
+impl Solution for Day6 {
+    const DAY: u32 = 6;
+
+    fn parse(input: &str) -> Result<Self, Error> {
+        Ok(Day6 { timers: parse_input(&lines(input))? })
+    }
+
+    fn part1(&self) -> Answer {
+        simulate(&self.timers, 80).expect("timers are checked when parsed").into()
+    }
+
+    fn part2(&self) -> Answer {
+        simulate(&self.timers, 256).expect("timers are checked when parsed").into()
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use aoc_common::read_lines;
     #[test]
     fn example() {
-        let input = [3,4,3,1,2];
-        println!("Part1: {}", simulate(&input, 80));
-        println!("Part2: {}", simulate(&input, 256));
+        let input = parse_input(&read_lines("example").unwrap()).unwrap();
+        println!("Part1: {}", simulate(&input, 80).unwrap());
+        println!("Part2: {}", simulate(&input, 256).unwrap());
     }
 
     #[test]
     fn actual() {
-        let input = [5,4,3,5,1,1,2,1,2,1,3,2,3,4,5,1,2,4,3,2,5,1,4,2,1,1,2,5,4,4,4,1,5,4,5,2,1,2,5,5,4,1,3,1,4,2,4,2,5,1,3,5,3,2,3,1,1,4,5,2,4,3,1,5,5,1,3,1,3,2,2,4,1,3,4,3,3,4,1,3,4,3,4,5,2,1,1,1,4,5,5,1,1,3,2,4,1,2,2,2,4,1,2,5,5,1,4,5,2,4,2,1,5,4,1,3,4,1,2,3,1,5,1,3,4,5,4,1,4,3,3,3,5,5,1,1,5,1,5,5,1,5,2,1,5,1,2,3,5,5,1,3,3,1,5,3,4,3,4,3,2,5,2,1,2,5,1,1,1,1,5,1,1,4,3,3,5,1,1,1,4,4,1,3,3,5,5,4,3,2,1,2,2,3,4,1,5,4,3,1,1,5,1,4,2,3,2,2,3,4,1,3,4,1,4,3,4,3,1,3,3,1,1,4,1,1,1,4,5,3,1,1,2,5,2,5,1,5,3,3,1,3,5,5,1,5,4,3,1,5,1,1,5,5,1,1,2,5,5,5,1,1,3,2,2,3,4,5,5,2,5,4,2,1,5,1,4,4,5,4,4,1,2,1,1,2,3,5,5,1,3,1,4,2,3,3,1,4,1,1];
-        println!("Part1: {}", simulate(&input, 80));
-        println!("Part2: {}", simulate(&input, 256));
+        let input = parse_input(&read_lines("input").unwrap()).unwrap();
+        println!("Part1: {}", simulate(&input, 80).unwrap());
+        println!("Part2: {}", simulate(&input, 256).unwrap());
+    }
+
+    #[test]
+    fn bad_input() {
+        assert_eq!(parse_input(&["3,4,x".to_owned()]), Err(ParseError::new(1, 5, "x", "a timer")));
+        assert_eq!(parse_input(&["3,9,1".to_owned()]), Err(ParseError::new(1, 3, "9", "a timer from 0 to 8")));
+        assert_eq!(parse_input(&[]), Err(ParseError::new(1, 1, "", "comma separated timers")));
     }
 }
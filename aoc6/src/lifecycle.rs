@@ -0,0 +1,204 @@
+use std::fmt;
+
+/// Most ages a population can track, which bounds the timers and death age
+pub const CAPACITY: usize = 256;
+
+/// Why fish with some timers under some lifecycle cannot be counted
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum PopulationError {
+    /// The lifecycle needs this many ages, not from 1 to `CAPACITY`
+    Ages(usize),
+    /// No fish in the lifecycle ever has this timer
+    Timer(u32),
+    /// Counting by timer cannot tell when a fish dies
+    Death,
+}
+
+impl fmt::Display for PopulationError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            PopulationError::Ages(ages) => write!(f, "the lifecycle needs {} ages, from 1 to {} are supported", ages, CAPACITY),
+            PopulationError::Timer(timer) => write!(f, "no fish ever has timer {} in the lifecycle", timer),
+            PopulationError::Death => write!(f, "fish that die can only be counted by age"),
+        }
+    }
+}
+
+impl std::error::Error for PopulationError {}
+
+/// Rules by which fish age, give birth and die
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct Lifecycle {
+    /// Timer of a fish that has just given birth
+    pub reset: usize,
+    /// Timer of a newborn
+    pub newborn: usize,
+    /// Days a newborn spends at the newborn timer before it starts counting down
+    pub maturation: usize,
+    /// Age in days at which a fish dies, if it ever does
+    pub death_age: Option<usize>,
+}
+
+/// The puzzle's lanternfish
+pub const LANTERNFISH: Lifecycle = Lifecycle { reset: 6, newborn: 8, maturation: 0, death_age: None };
+
+impl Lifecycle {
+    /// Age at which a newborn first gives birth
+    fn first_birth(&self) -> usize {
+        self.newborn + self.maturation + 1
+    }
+
+    /// Ages to track. Without death, a fish one full cycle past its first
+    /// birth behaves exactly as it did at its first birth, so ages wrap there.
+    fn slots(&self) -> usize {
+        self.death_age.unwrap_or(self.first_birth() + self.reset + 1)
+    }
+
+    /// Age of a fish with `timer`, taking it to be as young as its timer
+    /// allows. `None` if no fish ever has that timer.
+    pub fn age(&self, timer: usize) -> Option<usize> {
+        let age = match timer {
+            t if t == self.newborn => 0,
+            t if t < self.newborn => self.maturation + self.newborn - t,
+            t if t <= self.reset => self.first_birth() + self.reset - t,
+            _ => return None,
+        };
+        match age < self.slots() {
+            true => Some(age),
+            false => None,
+        }
+    }
+
+    /// Days left before a fish of `age` next gives birth, less one. This is
+    /// its timer, except while it is maturing.
+    pub fn countdown(&self, age: usize) -> usize {
+        match age < self.first_birth() {
+            true => self.first_birth() - 1 - age,
+            false => self.reset - (age - self.first_birth()) % (self.reset + 1),
+        }
+    }
+}
+
+/// Number of fish of each age. Ageing by a day only moves the start of the
+/// ring, so a step costs one pass over the birth ages rather than a rebuild.
+#[derive(Debug, Clone)]
+pub struct Population {
+    lifecycle: Lifecycle,
+    counts: [usize; CAPACITY],
+    /// Slot of the newborns
+    head: usize,
+}
+
+impl Population {
+    /// Population of fish with the given timers
+    pub fn new(lifecycle: Lifecycle, timers: &[u32]) -> Result<Self, PopulationError> {
+        let slots = lifecycle.slots();
+        if !(1..=CAPACITY).contains(&slots) {
+            return Err(PopulationError::Ages(slots));
+        }
+        let mut res = Population { lifecycle, counts: [0; CAPACITY], head: 0 };
+        for &timer in timers {
+            let age = lifecycle.age(timer as usize).ok_or(PopulationError::Timer(timer))?;
+            res.counts[age] += 1;
+        }
+        Ok(res)
+    }
+
+    fn slot(&self, age: usize) -> usize {
+        (self.head + age) % self.lifecycle.slots()
+    }
+
+    /// Number of fish that are `age` days old
+    pub fn count(&self, age: usize) -> usize {
+        match age < self.lifecycle.slots() {
+            true => self.counts[self.slot(age)],
+            false => 0,
+        }
+    }
+
+    pub fn total(&self) -> usize {
+        self.counts[..self.lifecycle.slots()].iter().sum()
+    }
+
+    /// Advance one day: every fish ages, the oldest die or start a new
+    /// cycle, and those at a birth age each have one newborn
+    pub fn step(&mut self) {
+        let slots = self.lifecycle.slots();
+        self.head = (self.head + slots - 1) % slots;
+        // The slot for newborns held the oldest fish until now
+        let oldest = std::mem::take(&mut self.counts[self.head]);
+        if self.lifecycle.death_age.is_none() {
+            let first_birth = self.slot(self.lifecycle.first_birth());
+            self.counts[first_birth] += oldest;
+        }
+        let births = (self.lifecycle.first_birth()..slots)
+            .step_by(self.lifecycle.reset + 1)
+            .map(|age| self.counts[self.slot(age)])
+            .sum();
+        self.counts[self.head] = births;
+    }
+}
+
+/// Total population on each day from the start to `days`
+pub fn population_history(timers: &[u32], days: usize, lifecycle: Lifecycle) -> Result<Vec<usize>, PopulationError> {
+    let mut population = Population::new(lifecycle, timers)?;
+    let mut res = vec![population.total()];
+    for _ in 0..days {
+        population.step();
+        res.push(population.total());
+    }
+    Ok(res)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    #[test]
+    fn lanternfish() {
+        let history = population_history(&[3, 4, 3, 1, 2], 18, LANTERNFISH).unwrap();
+        assert_eq!(&history[..5], &[5, 5, 6, 7, 9]);
+        assert_eq!(history[18], 26);
+
+        // Waiting to mature takes as long as starting from a higher timer,
+        // but the timer never goes above the newborn one
+        let slow = Lifecycle { maturation: 2, ..LANTERNFISH };
+        let late = Lifecycle { newborn: 10, ..LANTERNFISH };
+        assert_eq!(population_history(&[3, 4, 3, 1, 2], 100, slow).unwrap(), population_history(&[3, 4, 3, 1, 2], 100, late).unwrap());
+        assert_eq!((0..=10).map(|t| slow.age(t)).collect::<Vec<_>>(),
+            vec![Some(10), Some(9), Some(8), Some(7), Some(6), Some(5), Some(4), Some(3), Some(0), None, None]);
+        assert_eq!((late.age(8), late.age(10)), (Some(2), Some(0)));
+        // A fish at the newborn timer is taken to be newborn, so it waits as long as one at 10 in `late`
+        assert_eq!(population_history(&[8], 12, slow).unwrap(), population_history(&[10], 12, late).unwrap());
+        assert_eq!((0..12).map(|age| slow.countdown(age)).collect::<Vec<_>>(), vec![10, 9, 8, 7, 6, 5, 4, 3, 2, 1, 0, 6]);
+    }
+
+    #[test]
+    fn death() {
+        // Births every day from age 1, and death at 3
+        let lifecycle = Lifecycle { reset: 0, newborn: 0, maturation: 0, death_age: Some(3) };
+        assert_eq!(population_history(&[0], 6, lifecycle).unwrap(), vec![1, 2, 4, 6, 10, 16, 26]);
+        let mut population = Population::new(lifecycle, &[0]).unwrap();
+        population.step();
+        population.step();
+        assert_eq!((0..4).map(|age| population.count(age)).collect::<Vec<usize>>(), vec![2, 1, 1, 0]);
+
+        // Dying before the first birth leaves nobody
+        let lifecycle = Lifecycle { death_age: Some(9), ..LANTERNFISH };
+        assert_eq!(population_history(&[3, 4, 3, 1, 2], 10, lifecycle).unwrap().last(), Some(&0));
+        assert_eq!(lifecycle.age(0), Some(8));
+        assert_eq!(LANTERNFISH.age(9), None);
+    }
+
+    #[test]
+    fn unsupported() {
+        let long_lived = Lifecycle { death_age: Some(300), ..LANTERNFISH };
+        assert_eq!(population_history(&[3], 10, long_lived), Err(PopulationError::Ages(300)));
+        let stillborn = Lifecycle { death_age: Some(0), ..LANTERNFISH };
+        assert_eq!(Population::new(stillborn, &[]).unwrap_err(), PopulationError::Ages(0));
+        assert_eq!(Population::new(LANTERNFISH, &[3, 9]).unwrap_err(), PopulationError::Timer(9));
+        // A fish at timer 0 is at least 8 days old, past its death age
+        let short_lived = Lifecycle { death_age: Some(5), ..LANTERNFISH };
+        assert_eq!(population_history(&[0], 1, short_lived), Err(PopulationError::Timer(0)));
+        assert_eq!(PopulationError::Ages(300).to_string(), "the lifecycle needs 300 ages, from 1 to 256 are supported");
+    }
+}
@@ -0,0 +1,151 @@
+use aoc_common::Grid;
+use crate::{Lifecycle, Natural, PopulationError};
+
+/// Numbers that fish can be counted in
+pub trait Count: Clone {
+    /// The number `n` of the same kind, e.g. with the same modulus
+    fn with(&self, n: u64) -> Self;
+    fn add(&self, other: &Self) -> Self;
+    fn mul(&self, other: &Self) -> Self;
+}
+
+impl Count for Natural {
+    fn with(&self, n: u64) -> Self {
+        Natural::from(n)
+    }
+
+    fn add(&self, other: &Self) -> Self {
+        self + other
+    }
+
+    fn mul(&self, other: &Self) -> Self {
+        self * other
+    }
+}
+
+/// Residue modulo `modulus`, which must not be zero
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct Modular {
+    pub value: u64,
+    pub modulus: u64,
+}
+
+impl Count for Modular {
+    fn with(&self, n: u64) -> Self {
+        Modular { value: n % self.modulus, modulus: self.modulus }
+    }
+
+    fn add(&self, other: &Self) -> Self {
+        self.with(((self.value as u128 + other.value as u128) % self.modulus as u128) as u64)
+    }
+
+    fn mul(&self, other: &Self) -> Self {
+        self.with(((self.value as u128 * other.value as u128) % self.modulus as u128) as u64)
+    }
+}
+
+/// How many fish with countdown `i` tomorrow come from each fish with
+/// countdown `j` today, at `(i, j)`. The countdown is the timer, counting
+/// the days spent maturing on top of the newborn timer, so this is 9×9 for
+/// lanternfish. Only lifecycles without death fit, since the countdown says
+/// nothing about age; others are `PopulationError::Death`.
+pub fn transition<T: Count>(lifecycle: Lifecycle, unit: &T) -> Result<Grid<T>, PopulationError> {
+    if lifecycle.death_age.is_some() {
+        return Err(PopulationError::Death);
+    }
+    let newborn = lifecycle.newborn + lifecycle.maturation;
+    let n = newborn.max(lifecycle.reset) + 1;
+    Ok(Grid::from_fn(n, n, |(i, j)| {
+        let ways = match j {
+            0 => (i == lifecycle.reset) as u64 + (i == newborn) as u64,
+            _ => (i + 1 == j) as u64,
+        };
+        unit.with(ways)
+    }))
+}
+
+fn multiply<T: Count>(a: &Grid<T>, b: &Grid<T>) -> Grid<T> {
+    let n = a.width();
+    Grid::from_fn(n, n, |(i, j)| {
+        (0..n).fold(a[(0, 0)].with(0), |acc, k| acc.add(&a[(i, k)].mul(&b[(k, j)])))
+    })
+}
+
+/// `m` raised to `exp`, by repeated squaring
+pub fn power<T: Count>(m: &Grid<T>, mut exp: u64) -> Grid<T> {
+    let n = m.width();
+    let mut res = Grid::from_fn(n, n, |(i, j)| m[(0, 0)].with((i == j) as u64));
+    let mut base = m.clone();
+    while exp > 0 {
+        if exp & 1 == 1 {
+            res = multiply(&res, &base);
+        }
+        exp >>= 1;
+        if exp > 0 {
+            base = multiply(&base, &base);
+        }
+    }
+    res
+}
+
+/// Number of fish after `days`, counted in the kind of number `unit` is,
+/// with O(log days) matrix products
+pub fn population<T: Count>(timers: &[u32], days: u64, lifecycle: Lifecycle, unit: &T) -> Result<T, PopulationError> {
+    let m = power(&transition(lifecycle, unit)?, days);
+    let mut start = vec![0; m.width()];
+    for &timer in timers {
+        let age = lifecycle.age(timer as usize).ok_or(PopulationError::Timer(timer))?;
+        start[lifecycle.countdown(age)] += 1;
+    }
+    // Total over every timer of the fish descended from each starting timer
+    Ok(m.positions().fold(unit.with(0), |acc, (i, j)| acc.add(&m[(i, j)].mul(&unit.with(start[j])))))
+}
+
+/// Exact number of fish after `days`
+pub fn population_exact(timers: &[u32], days: u64, lifecycle: Lifecycle) -> Result<Natural, PopulationError> {
+    population(timers, days, lifecycle, &Natural::from(1))
+}
+
+/// Number of fish after `days` modulo `modulus`, e.g. a prime
+pub fn population_mod(timers: &[u32], days: u64, lifecycle: Lifecycle, modulus: u64) -> Result<u64, PopulationError> {
+    assert!(modulus > 0, "modulus must not be zero");
+    population(timers, days, lifecycle, &Modular { value: 1, modulus }).map(|n| n.value)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{population_history, simulate, LANTERNFISH};
+    #[test]
+    fn matches_simulation() {
+        let timers = [3, 4, 3, 1, 2, 8];
+        assert_eq!(transition(LANTERNFISH, &Natural::from(1)).unwrap().width(), 9);
+        assert_eq!(population_exact(&timers, 256, LANTERNFISH).unwrap().to_string(), simulate(&timers, 256).unwrap().to_string());
+        assert_eq!(transition(Lifecycle { maturation: 2, ..LANTERNFISH }, &Natural::from(1)).unwrap().width(), 11);
+        let slow = Lifecycle { reset: 4, maturation: 3, ..LANTERNFISH };
+        for (days, &total) in population_history(&timers, 60, slow).unwrap().iter().enumerate() {
+            assert_eq!(population_exact(&timers, days as u64, slow).unwrap(), Natural::from(total as u64));
+            assert_eq!(population_mod(&timers, days as u64, slow, 101).unwrap(), total as u64 % 101);
+        }
+    }
+
+    #[test]
+    fn far_future() {
+        let timers = [3, 4, 3, 1, 2];
+        let exact = population_exact(&timers, 1000, LANTERNFISH).unwrap();
+        assert_eq!(exact.to_string(), "379589061144698259131825683795505058481");
+        let big = population_exact(&timers, 5000, LANTERNFISH).unwrap();
+        let p = (1 << 61) - 1;
+        assert_eq!(population_mod(&timers, 5000, LANTERNFISH, p).unwrap(), big.rem_u64(p));
+        assert_eq!(big.rem_u64(p), 901953804912594393);
+        assert_eq!(population_mod(&timers, 1_000_000_000_000_000_000, LANTERNFISH, 1_000_000_007).unwrap(), 860170227);
+        assert_eq!(population_mod(&timers, 1_000_000_000_000_000_000, LANTERNFISH, 998244353).unwrap(), 90370603);
+    }
+
+    #[test]
+    fn unsupported() {
+        let mortal = Lifecycle { death_age: Some(20), ..LANTERNFISH };
+        assert_eq!(population_exact(&[3], 10, mortal), Err(PopulationError::Death));
+        assert_eq!(population_mod(&[3, 4, 12], 10, LANTERNFISH, 7), Err(PopulationError::Timer(12)));
+    }
+}
@@ -0,0 +1,121 @@
+use std::fmt;
+use std::ops::{Add, Mul};
+
+/// Natural number of any size, as base 2^32 digits from the least
+/// significant, without trailing zero digits
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct Natural {
+    digits: Vec<u32>,
+}
+
+impl Natural {
+    fn trimmed(mut digits: Vec<u32>) -> Self {
+        while digits.last() == Some(&0) {
+            digits.pop();
+        }
+        Natural { digits }
+    }
+
+    pub fn is_zero(&self) -> bool {
+        self.digits.is_empty()
+    }
+
+    /// Remainder after dividing by `m`, which must not be zero
+    pub fn rem_u64(&self, m: u64) -> u64 {
+        self.digits.iter().rev().fold(0, |acc, &d| ((((acc as u128) << 32) | d as u128) % m as u128) as u64)
+    }
+
+    /// Quotient and remainder after dividing by `d`, which must not be zero
+    fn div_rem_u32(&self, d: u32) -> (Natural, u32) {
+        let mut rem = 0u64;
+        let mut digits = vec![0; self.digits.len()];
+        for (k, &digit) in self.digits.iter().enumerate().rev() {
+            let cur = (rem << 32) | digit as u64;
+            digits[k] = (cur / d as u64) as u32;
+            rem = cur % d as u64;
+        }
+        (Natural::trimmed(digits), rem as u32)
+    }
+}
+
+impl From<u64> for Natural {
+    fn from(n: u64) -> Self {
+        Natural::trimmed(vec![n as u32, (n >> 32) as u32])
+    }
+}
+
+impl Add for &Natural {
+    type Output = Natural;
+
+    fn add(self, other: &Natural) -> Natural {
+        let (long, short) = match self.digits.len() >= other.digits.len() {
+            true => (self, other),
+            false => (other, self),
+        };
+        let mut digits = Vec::with_capacity(long.digits.len() + 1);
+        let mut carry = 0u64;
+        for (k, &d) in long.digits.iter().enumerate() {
+            let sum = d as u64 + *short.digits.get(k).unwrap_or(&0) as u64 + carry;
+            digits.push(sum as u32);
+            carry = sum >> 32;
+        }
+        digits.push(carry as u32);
+        Natural::trimmed(digits)
+    }
+}
+
+impl Mul for &Natural {
+    type Output = Natural;
+
+    fn mul(self, other: &Natural) -> Natural {
+        let mut digits = vec![0u32; self.digits.len() + other.digits.len()];
+        for (i, &a) in self.digits.iter().enumerate() {
+            let mut carry = 0u64;
+            for (j, &b) in other.digits.iter().enumerate() {
+                let cur = digits[i + j] as u64 + a as u64 * b as u64 + carry;
+                digits[i + j] = cur as u32;
+                carry = cur >> 32;
+            }
+            digits[i + other.digits.len()] = carry as u32;
+        }
+        Natural::trimmed(digits)
+    }
+}
+
+impl fmt::Display for Natural {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        // Nine decimal digits at a time, from the least significant
+        let mut chunks = vec![];
+        let mut n = self.clone();
+        while !n.is_zero() {
+            let (q, r) = n.div_rem_u32(1_000_000_000);
+            chunks.push(r);
+            n = q;
+        }
+        match chunks.pop() {
+            Some(first) => write!(f, "{}", first)?,
+            None => return write!(f, "0"),
+        }
+        for chunk in chunks.iter().rev() {
+            write!(f, "{:09}", chunk)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    #[test]
+    fn arithmetic() {
+        let a = Natural::from(u64::MAX);
+        let b = &a * &a;
+        assert_eq!(b.to_string(), (u64::MAX as u128 * u64::MAX as u128).to_string());
+        assert_eq!((&b + &Natural::from(1)).to_string(), "340282366920938463426481119284349108226");
+        assert_eq!((&a + &a).to_string(), (2 * u64::MAX as u128).to_string());
+        assert_eq!(b.rem_u64(1_000_000_007), (u64::MAX as u128 * u64::MAX as u128 % 1_000_000_007) as u64);
+        assert_eq!(Natural::from(0).to_string(), "0");
+        assert_eq!(Natural::from(1_000_000_000).to_string(), "1000000000");
+        assert!(Natural::from(0).is_zero() && (&Natural::from(0) * &a).is_zero());
+    }
+}
@@ -1,43 +1,50 @@
-use std::io::{BufReader, Error, ErrorKind};
-use std::io::prelude::*;
-use std::fs::File;
 use std::collections::HashMap;
+use aoc_common::{column, lines, split_field, Answer, Error, ParseError, Solution};
 
-fn read_input(filename: &str) -> Result<Vec<String>, Error> {
-    let f = File::open(filename).unwrap();
-    let f = BufReader::new(f);
-    f.lines().map(|l| l.and_then(|v| v.parse().map_err(|e| Error::new(ErrorKind::InvalidData, e)))).collect()
-}
+/// Lit segments a-g of one seven segment pattern
+pub type Pattern = [bool; 7];
+
+/// Unique signal patterns and output digits of every display
+pub type Notes = (Vec<Vec<Pattern>>, Vec<Vec<Pattern>>);
 
-fn parse_input(input: &Vec<String>) -> (Vec<Vec<[bool; 7]>>, Vec<Vec<[bool; 7]>>) {
+pub fn parse_input(input: &[String]) -> Result<Notes, ParseError> {
     let mut unique_signals = vec![];
     let mut output = vec![];
-    let bool_array = |v: &str| {
-        let mut arr = [false; 7];
-        for c in v.chars() {
-            arr[(c as u32 - 97) as usize] = true;
-        }
-        arr
-    };
-    for val in input.iter() {
-        let split_val: Vec<&str> = val.split("|").collect::<Vec<&str>>();
-        unique_signals.push(split_val[0].trim().split(" ")
-            .map(|v| bool_array(v)).collect());
-        output.push(split_val[1].trim().split(" ")
-            .map(|v| bool_array(v)).collect());
+    for (i, val) in input.iter().enumerate() {
+        let bool_array = |v: &str| -> Result<Pattern, ParseError> {
+            let mut arr = [false; 7];
+            for (j, c) in v.char_indices() {
+                match c {
+                    'a'..='g' => arr[(c as u32 - 97) as usize] = true,
+                    _ => return Err(ParseError::new(i + 1, column(val, v) + j, &v[j..], "segments a-g")),
+                }
+            }
+            Ok(arr)
+        };
+        let patterns = |s: &str, count: usize| -> Result<Vec<Pattern>, ParseError> {
+            let s = s.trim();
+            let res = s.split(' ').map(bool_array).collect::<Result<Vec<Pattern>, ParseError>>()?;
+            if res.len() != count {
+                return Err(ParseError::new(i + 1, column(val, s), s, &format!("{} patterns", count)));
+            }
+            Ok(res)
+        };
+        let (signals, digits) = split_field(i + 1, val, val, "|", "signal patterns | output digits")?;
+        unique_signals.push(patterns(signals, 10)?);
+        output.push(patterns(digits, 4)?);
     }
-    (unique_signals, output)
+    Ok((unique_signals, output))
 }
 
-fn count_1478(output: &Vec<Vec<[bool; 7]>>) -> usize {
+pub fn count_1478(output: &[Vec<Pattern>]) -> usize {
     output.iter()
         .map(|line| line.iter()
-            .map(|val| val.iter().filter(|v| **v == true).count())
-            .map(|val| val == 2 || val == 3 || val == 4 || val == 7).filter(|v1| *v1 == true).count())
+            .map(|val| val.iter().filter(|v| **v).count())
+            .map(|val| val == 2 || val == 3 || val == 4 || val == 7).filter(|v1| *v1).count())
         .sum()
 }
 
-fn evaluate(mut input: Vec<Vec<[bool; 7]>>, output: &Vec<Vec<[bool; 7]>>) -> usize {
+pub fn evaluate(mut input: Vec<Vec<Pattern>>, output: &[Vec<Pattern>]) -> usize {
     let mut map = HashMap::new();
     map.insert([false, false, true, false, false, true, false], 1); // cf - 1
     map.insert([true, false, true, false, false, true, false], 7); // acf - 7
@@ -61,11 +68,11 @@ fn evaluate(mut input: Vec<Vec<[bool; 7]>>, output: &Vec<Vec<[bool; 7]>>) -> usi
     total
 }
 
-fn evaluate_row(input: &Vec<[bool; 7]>) -> HashMap<[bool; 7], usize> {
+fn evaluate_row(input: &[Pattern]) -> HashMap<Pattern, usize> {
     // Sort by number of true -> order [2, 3, 4, 5, 5, 5, 6, 6, 6, 7]
-    let mut sorted_input: Vec<[bool; 7]> = input.clone();
-    sorted_input.sort_by_key(|val| val.iter().filter(|v| **v == true).count());
-    let mut mapping: HashMap<[bool; 7], usize> = HashMap::new();
+    let mut sorted_input: Vec<Pattern> = input.to_vec();
+    sorted_input.sort_by_key(|val| val.iter().filter(|v| **v).count());
+    let mut mapping: HashMap<Pattern, usize> = HashMap::new();
 
     // Length 2 => 1
     mapping.insert(sorted_input[0], 1);
@@ -79,8 +86,8 @@ fn evaluate_row(input: &Vec<[bool; 7]>) -> HashMap<[bool; 7], usize> {
     // Length 7 is 8
     mapping.insert(sorted_input[9], 8);
 
-    let match_helper = |val1: &[bool; 7], val2: &[bool; 7]| -> usize {
-        val1.iter().zip(val2.iter()).map(|(a, b)| match *a == true && *b == true {
+    let match_helper = |val1: &Pattern, val2: &Pattern| -> usize {
+        val1.iter().zip(val2.iter()).map(|(a, b)| match *a && *b {
             true => 1,
             false => 0
         }).sum()
@@ -88,8 +95,8 @@ fn evaluate_row(input: &Vec<[bool; 7]>) -> HashMap<[bool; 7], usize> {
 
     // Among length 5, if 2 letters match with 1 => 3 else if 3 letters match with 4 => 5, else 2
     for val in sorted_input[3..6].iter() {
-        let mut num_matches_with_1 = match_helper(val, &sorted_input[0]); // Index 0 is 1
-        let mut num_matches_with_4 = match_helper(val, &sorted_input[2]); // Index 2 is 4
+        let num_matches_with_1 = match_helper(val, &sorted_input[0]); // Index 0 is 1
+        let num_matches_with_4 = match_helper(val, &sorted_input[2]); // Index 2 is 4
         if num_matches_with_1 == 2 {
             mapping.insert(*val, 3);
         } else if num_matches_with_4 == 3 {
@@ -101,8 +108,8 @@ fn evaluate_row(input: &Vec<[bool; 7]>) -> HashMap<[bool; 7], usize> {
 
     // Among length 6, if 1 letter matches with 1 it is 6, else if 4 letters match with 4 it is 9, else 0
     for val in sorted_input[6..9].iter() {
-        let mut num_matches_with_1 = match_helper(val, &sorted_input[0]);
-        let mut num_matches_with_4 = match_helper(val, &sorted_input[2]);
+        let num_matches_with_1 = match_helper(val, &sorted_input[0]);
+        let num_matches_with_4 = match_helper(val, &sorted_input[2]);
         if num_matches_with_1 == 1 {
             mapping.insert(*val, 6);
         } else if num_matches_with_4 == 4 {
@@ -114,19 +121,43 @@ fn evaluate_row(input: &Vec<[bool; 7]>) -> HashMap<[bool; 7], usize> {
     mapping
 }
 
+/// Unique signal patterns and four digit outputs of every display
+pub struct Day8 {
+    unique_signals: Vec<Vec<Pattern>>,
+    output: Vec<Vec<Pattern>>,
+}
+
+impl Solution for Day8 {
+    const DAY: u32 = 8;
+
+    fn parse(input: &str) -> Result<Self, Error> {
+        let (unique_signals, output) = parse_input(&lines(input))?;
+        Ok(Day8 { unique_signals, output })
+    }
+
+    fn part1(&self) -> Answer {
+        count_1478(&self.output).into()
+    }
+
+    fn part2(&self) -> Answer {
+        evaluate(self.unique_signals.clone(), &self.output).into()
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use aoc_common::read_lines;
     #[test]
     fn example() {
-        let (unique_signals, output) = parse_input(&read_input("example").unwrap());
+        let (unique_signals, output) = parse_input(&read_lines("example").unwrap()).unwrap();
         println!("Part1: {}", count_1478(&output));
         println!("Part2: {}", evaluate(unique_signals, &output));
     }
 
     #[test]
     fn actual() {
-        let (unique_signals, output) = parse_input(&read_input("input").unwrap());
+        let (unique_signals, output) = parse_input(&read_lines("input").unwrap()).unwrap();
         println!("Part1: {}", count_1478(&output));
         println!("Part2: {}", evaluate(unique_signals, &output));
     }
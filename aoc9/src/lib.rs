@@ -1,78 +1,45 @@
-use std::io::{BufReader, Error, ErrorKind};
-use std::io::prelude::*;
-use std::fs::File;
 use std::collections::VecDeque;
+use aoc_common::{lines, Answer, Error, Grid, ParseError, Pos, Solution};
 
-fn read_input(filename: &str) -> Result<Vec<Vec<i8>>, Error> {
-    let f = File::open(filename).unwrap();
-    let f = BufReader::new(f);
-    f.lines()
-        .map(|l| l.and_then(|v| v.parse().map_err(|e| Error::new(ErrorKind::InvalidData, e)))
-                  .and_then(|v: String| Ok(v.into_bytes().iter().map(|x| return (x - 48) as i8).collect()))).collect()
+/// Convert each line of digits into a row of heights
+pub fn parse_input(input: &[String]) -> Result<Grid<u8>, ParseError> {
+    Grid::from_lines(input)
 }
 
-fn get_neighbors(input: &Vec<Vec<i8>>, i: usize, j: usize) -> (i8, i8, i8, i8) {
-    let left = match j > 0 {
-        true => input[i][j - 1],
-        false => 10,
-    };
-    let right = match j < input[0].len() - 1 {
-        true => input[i][j + 1],
-        false => 10,
-    };
-    let top = match i > 0 {
-        true => input[i - 1][j],
-        false => 10,
-    };
-    let bottom = match i < input.len() - 1 {
-        true => input[i + 1][j],
-        false => 10,
-    };
-    (left, right, top, bottom)
-}
-
-fn count_valleys(input: &Vec<Vec<i8>>) -> (u64, Vec<(usize, usize)>) {
+pub fn count_valleys(input: &Grid<u8>) -> (u64, Vec<Pos>) {
     let mut res = 0;
     let mut valley_points = vec![];
-    for i in 0..input.len() {
-        for j in 0..input[0].len() {
-            let val = input[i][j];
-            let (left, right, top, bottom) = get_neighbors(input, i, j);
-            if val < left && val < right && val < top && val < bottom {
-                res += (val + 1) as u64;
-                valley_points.push((i, j));
-            }
+    for pos in input.positions() {
+        let val = input[pos];
+        if input.neighbors4(pos).all(|n| val < input[n]) {
+            res += (val + 1) as u64;
+            valley_points.push(pos);
         }
     }
     (res, valley_points)
 }
 
-fn bfs(input: &Vec<Vec<i8>>, valley_points: &Vec<(usize, usize)>) -> usize {
-    let mut visited: Vec<Vec<i16>> = vec![vec![-1; input[0].len()]; input.len()];
-    
-    let mut helper = |start: (usize, usize), index: usize| {
+pub fn bfs(input: &Grid<u8>, valley_points: &[Pos]) -> usize {
+    let mut visited: Grid<i16> = Grid::new(input.width(), input.height(), -1);
+
+    let mut helper = |start: Pos, index: usize| {
         let mut q = VecDeque::new();
         q.push_back(start);
         while !q.is_empty() {
-            let (i, j) = q.pop_front().unwrap();
-            visited[i][j] = index as i16;
-            for (x, y) in vec![(i as i16 + 1, j as i16), (i as i16- 1, j as i16), (i as i16, j as i16 + 1), (i as i16, j as i16 - 1)] {
-                if x < 0 || y < 0 {
-                    continue;
-                }
-                let (x, y) = (x as usize, y as usize);
-                if x < input.len() && y < input[0].len() && visited[x][y] < 0 && input[x][y] > input[i][j] && input[x][y] != 9 {
-                    q.push_back((x, y));
+            let pos = q.pop_front().unwrap();
+            visited[pos] = index as i16;
+            for next in input.neighbors4(pos) {
+                if visited[next] < 0 && input[next] > input[pos] && input[next] != 9 {
+                    q.push_back(next);
                 }
             }
         }
     };
-    for i in 0..valley_points.len() {
-        helper(valley_points[i], i);
+    for (i, point) in valley_points.iter().enumerate() {
+        helper(*point, i);
     }
     let mut res = vec![0; valley_points.len()];
-    let visited: Vec<i16> = visited.into_iter().flatten().collect();
-    for val in visited {
+    for &val in visited.iter() {
         if val < 0 {
             continue;
         }
@@ -83,12 +50,35 @@ fn bfs(input: &Vec<Vec<i8>>, valley_points: &Vec<(usize, usize)>) -> usize {
     res[0] * res[1] * res[2]
 }
 
+/// Heightmap of the cave floor
+pub struct Day9 {
+    heights: Grid<u8>,
+}
+
+impl Solution for Day9 {
+    const DAY: u32 = 9;
+
+    fn parse(input: &str) -> Result<Self, Error> {
+        Ok(Day9 { heights: parse_input(&lines(input))? })
+    }
+
+    fn part1(&self) -> Answer {
+        count_valleys(&self.heights).0.into()
+    }
+
+    fn part2(&self) -> Answer {
+        let (_, valley_points) = count_valleys(&self.heights);
+        bfs(&self.heights, &valley_points).into()
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use aoc_common::read_lines;
     #[test]
     fn example() {
-        let input = read_input("example").unwrap();
+        let input = parse_input(&read_lines("example").unwrap()).unwrap();
         let (height, valley_points) = count_valleys(&input);
         println!("Part1: {}", height);
         println!("Part2: {}", bfs(&input, &valley_points));
@@ -96,7 +86,7 @@ mod tests {
 
     #[test]
     fn actual() {
-        let input = read_input("input").unwrap();
+        let input = parse_input(&read_lines("input").unwrap()).unwrap();
         let (height, valley_points) = count_valleys(&input);
         println!("Part1: {}", height);
         println!("Part2: {}", bfs(&input, &valley_points));